@@ -1,9 +1,10 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use rosolio::by_nom::NoteNom;
+use rosolio::core::NoteParser;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("nom parse C♭𝄫5", |b| {
-        b.iter(|| NoteNom::parse("C♭𝄫5"))
+        b.iter(|| NoteNom::parse(black_box("C♭𝄫5")))
     });
 }
 
@@ -1,9 +1,10 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use rosolio::by_pest::NotePest;
+use rosolio::core::NoteParser;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("pest parse C♭𝄫5", |b| {
-        b.iter(|| NotePest::parse("C♭𝄫5"))
+        b.iter(|| NotePest::parse(black_box("C♭𝄫5")))
     });
 }
 
@@ -1,9 +1,10 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use rosolio::by_regex::NoteRegex;
+use rosolio::core::NoteParser;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("regex parse C♭𝄫5", |b| {
-        b.iter(|| NoteRegex::parse("C♭𝄫5"))
+        b.iter(|| NoteRegex::parse(black_box("C♭𝄫5")))
     });
 }
 
@@ -1,110 +1,55 @@
-extern crate pest;
-
 use pest::Parser;
+use pest_derive::Parser;
 
-use super::{Note, NoteBuilder};
+use crate::core::converters::note_converter::{parse_note, NoteParts};
+use crate::core::{Note, NoteParseError, NoteParser, ParseOptions};
 
 #[derive(Parser)]
 #[grammar = "note.pest"]
-pub struct NoteParser;
+struct NoteGrammar;
 
-pub struct NotePest {}
+/// Note parser generated by pest from `note.pest`.
+pub struct NotePest;
 
-impl NotePest {
-    pub fn parse(note: &str) -> Result<Note, String> {
-        /*
-        let tokens = pest::state::<(), _>(note, |s| {
-            println!("s: {:?}", s);
-            Ok(s)
-        })
-        .unwrap();
-        */
-        let mut tokens = NoteParser::parse(Rule::note, note)
-            .map_err(|_| format!("Improper note format: {}", note))?;
-        let mut note_builder = NoteBuilder::new();
-        note_builder.name(note);
-        for pair in tokens
-            .next()
-            .ok_or(format!("Improper note format: {}", note))?
-            .into_inner()
-        {
-            match pair.as_rule() {
-                Rule::name => {
-                    note_builder.pitch(pair.as_str());
-                }
-                Rule::accidental => {
-                    note_builder.accidental(pair.as_str());
-                }
-                Rule::octave => {
-                    note_builder.octave(pair.as_str().parse().unwrap_or(0));
-                }
-                Rule::cents => {
-                    note_builder.cents(pair.as_str().parse().unwrap_or(0));
-                }
-                _ => {
-                    //println!(
-                    //    "unexpected pair rule: {:?}, value: {:?}",
-                    //    pair.as_rule(),
-                    //    pair.as_str()
-                    //);
+impl NoteParser for NotePest {
+    fn parse_with(note: &str, options: &ParseOptions) -> Result<Note, NoteParseError> {
+        parse_note(note, options, |note| {
+            let tokens = NoteGrammar::parse(Rule::note, note).ok()?.next()?;
+            let mut parts = NoteParts {
+                pitchname: 0..0,
+                accidental: 0..0,
+                octave: 0..0,
+                cents: 0..0,
+                end: tokens.as_span().end(),
+            };
+            for pair in tokens.into_inner() {
+                let span = pair.as_span().start()..pair.as_span().end();
+                match pair.as_rule() {
+                    Rule::name => parts.pitchname = span,
+                    Rule::accidental => parts.accidental = span,
+                    Rule::octave => parts.octave = span,
+                    Rule::cents => parts.cents = span,
+                    _ => {}
                 }
             }
-            /*
-            println!(
-                "pair rule: {:?}, value: {:?}",
-                pair.as_rule(),
-                pair.as_str()
-            );
-            */
-        }
-        Ok(note_builder.build())
+            Some(parts)
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::NotePest as Note;
+    use super::NotePest;
+    use crate::core::converters::note_converter::assert_conformance;
+    use crate::core::NoteParser;
 
     #[test]
     fn pest_parse_test() {
-        /// >>> librosa.note_to_midi('C')
-        /// 12
-        /// >>> librosa.note_to_midi('C#3')
-        /// 49
-        /// >>> librosa.note_to_midi('C♯3')  # Using Unicode sharp
-        /// 49
-        /// >>> librosa.note_to_midi('C♭3')  # Using Unicode flat
-        /// 47
-        /// >>> librosa.note_to_midi('f4')
-        /// 65
-        /// >>> librosa.note_to_midi('Bb-1')
-        /// 10
-        /// >>> librosa.note_to_midi('A!8')
-        /// 116
-        /// >>> librosa.note_to_midi('G𝄪6')  # Double-sharp
-        /// 93
-        /// >>> librosa.note_to_midi('B𝄫6')  # Double-flat
-        /// 93
-        /// >>> librosa.note_to_midi('C♭𝄫5')  # Triple-flats also work
-        /// 69
-        /// >>> # Lists of notes also work
-        /// >>> librosa.note_to_midi(['C', 'E', 'G'])
-        /// array([12, 16, 19])
-        let v = Note::parse("C").unwrap();
-        assert_eq!(v.pitch(), 12, "C parsed to: {:?}", v);
-        let v = Note::parse("C#3").unwrap();
-        assert_eq!(v.pitch(), 49, "C#3 parsed to: {:?}", v);
-        let v = Note::parse("f4").unwrap();
-        assert_eq!(v.pitch(), 65, "f4 parsed to: {:?}", v);
-        let v = Note::parse("Bb-1").unwrap();
-        assert_eq!(v.pitch(), 10, "Bb-1 parsed to: {:?}", v);
-        let v = Note::parse("A!8").unwrap();
-        assert_eq!(v.pitch(), 116, "A!8 parsed to: {:?}", v);
-        let v = Note::parse("G𝄪6").unwrap();
-        assert_eq!(v.pitch(), 93, "G𝄪6 parsed to: {:?}", v);
-        let v = Note::parse("B𝄫6").unwrap();
-        assert_eq!(v.pitch(), 93, "B𝄫6 parsed to: {:?}", v);
-        let v = Note::parse("C♭𝄫5").unwrap();
-        assert_eq!(v.pitch(), 69, "C♭𝄫5 parsed to: {:?}", v);
+        assert_conformance::<NotePest>();
+        let v = NotePest::parse("Z♭𝄫5").unwrap_err();
+        assert_eq!(
+            "invalid pitch name, expected one of A, B, C, D, E, F, G",
+            &v.to_string()
+        );
     }
 }
@@ -0,0 +1,593 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::converters::note_converter::{self, ParseOptions};
+use super::note::Note;
+use super::NoteParseError;
+
+// Semitones above the root of the unaltered scale degrees 1 to 13.
+const DEGREE_TO_SEMITONES: [i8; 13] = [0, 2, 4, 5, 7, 9, 11, 12, 14, 16, 17, 19, 21];
+
+/// (degree, semitones above the root) of each chord tone.
+type Intervals = &'static [(u8, i8)];
+
+/// Harte shorthand, lead-sheet spelling and intervals of each chord quality.
+const QUALITIES: [(&str, &str, Intervals); 21] = [
+    ("maj", "", &[(1, 0), (3, 4), (5, 7)]),
+    ("min", "m", &[(1, 0), (3, 3), (5, 7)]),
+    ("dim", "dim", &[(1, 0), (3, 3), (5, 6)]),
+    ("aug", "aug", &[(1, 0), (3, 4), (5, 8)]),
+    ("sus2", "sus2", &[(1, 0), (2, 2), (5, 7)]),
+    ("sus4", "sus4", &[(1, 0), (4, 5), (5, 7)]),
+    ("5", "5", &[(1, 0), (5, 7)]),
+    ("maj6", "6", &[(1, 0), (3, 4), (5, 7), (6, 9)]),
+    ("min6", "m6", &[(1, 0), (3, 3), (5, 7), (6, 9)]),
+    ("7", "7", &[(1, 0), (3, 4), (5, 7), (7, 10)]),
+    ("maj7", "maj7", &[(1, 0), (3, 4), (5, 7), (7, 11)]),
+    ("min7", "m7", &[(1, 0), (3, 3), (5, 7), (7, 10)]),
+    ("minmaj7", "mMaj7", &[(1, 0), (3, 3), (5, 7), (7, 11)]),
+    ("dim7", "dim7", &[(1, 0), (3, 3), (5, 6), (7, 9)]),
+    ("hdim7", "m7b5", &[(1, 0), (3, 3), (5, 6), (7, 10)]),
+    ("7sus4", "7sus4", &[(1, 0), (4, 5), (5, 7), (7, 10)]),
+    ("9", "9", &[(1, 0), (3, 4), (5, 7), (7, 10), (9, 14)]),
+    ("maj9", "maj9", &[(1, 0), (3, 4), (5, 7), (7, 11), (9, 14)]),
+    ("min9", "m9", &[(1, 0), (3, 3), (5, 7), (7, 10), (9, 14)]),
+    (
+        "11",
+        "11",
+        &[(1, 0), (3, 4), (5, 7), (7, 10), (9, 14), (11, 17)],
+    ),
+    (
+        "13",
+        "13",
+        &[(1, 0), (3, 4), (5, 7), (7, 10), (9, 14), (11, 17), (13, 21)],
+    ),
+];
+
+/// Other lead-sheet spellings of the qualities, by Harte shorthand.
+const ALIASES: [(&str, &str); 27] = [
+    ("M", "maj"),
+    ("maj", "maj"),
+    ("min", "min"),
+    ("-", "min"),
+    ("°", "dim"),
+    ("o", "dim"),
+    ("+", "aug"),
+    ("sus", "sus4"),
+    ("min6", "min6"),
+    ("-6", "min6"),
+    ("M7", "maj7"),
+    ("Δ", "maj7"),
+    ("Δ7", "maj7"),
+    ("min7", "min7"),
+    ("-7", "min7"),
+    ("mM7", "minmaj7"),
+    ("m(maj7)", "minmaj7"),
+    ("minmaj7", "minmaj7"),
+    ("°7", "dim7"),
+    ("o7", "dim7"),
+    ("ø", "hdim7"),
+    ("ø7", "hdim7"),
+    ("min7b5", "hdim7"),
+    ("-7b5", "hdim7"),
+    ("7sus", "7sus4"),
+    ("M9", "maj9"),
+    ("min9", "min9"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ChordParseError {
+    #[error("invalid root: {0}")]
+    Root(NoteParseError),
+    #[error("unknown chord quality: {0}")]
+    UnknownQuality(String),
+    #[error("invalid interval degree: {0}")]
+    InvalidDegree(String),
+    #[error("invalid bass: {0}")]
+    InvalidBass(String),
+}
+
+/// A scale degree added to, altered in or (with `omit`) removed from a chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Degree {
+    degree: u8,
+    accidental: i8,
+    omit: bool,
+}
+
+impl Degree {
+    fn semitones(&self) -> i8 {
+        DEGREE_TO_SEMITONES[self.degree as usize - 1] + self.accidental
+    }
+
+    fn accidental_str(&self) -> String {
+        let symbol = if self.accidental < 0 { "b" } else { "#" };
+        symbol.repeat(self.accidental.unsigned_abs() as usize)
+    }
+}
+
+/// Parse `[b#♭♯]*[0-9]+` from the start of `input`, returning the rest,
+/// with at most a quadruple sharp or flat like a [`Note`].
+fn parse_degree(input: &str) -> Option<(u8, i8, &str)> {
+    let digits_at = input.find(|c: char| !matches!(c, 'b' | '#' | '♭' | '♯'))?;
+    let max = note_converter::MAX_QUARTER_TONES / note_converter::QUARTER_TONES_PER_SEMITONE;
+    let accidental = input[..digits_at]
+        .chars()
+        .map(|c| if matches!(c, 'b' | '♭') { -1 } else { 1 })
+        .try_fold(0_i8, |sum, step| sum.checked_add(step))
+        .filter(|accidental| accidental.abs() <= max)?;
+    let rest = &input[digits_at..];
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let degree = rest[..digits].parse().ok()?;
+    if (1..=13).contains(&degree) {
+        Some((degree, accidental, &rest[digits..]))
+    } else {
+        None
+    }
+}
+
+/// Parse a list of degrees such as `(#9)`, `b9#11`, `add9`, `(*5,b3)` or `no5`.
+fn parse_degrees(mut input: &str) -> Result<Vec<Degree>, ChordParseError> {
+    let invalid = |rest: &str| ChordParseError::InvalidDegree(rest.to_string());
+    let mut degrees = Vec::new();
+    loop {
+        input = input.trim_start_matches(['(', ')', ',', ' ']);
+        if input.is_empty() {
+            return Ok(degrees);
+        }
+        let (omit, rest) = if let Some(rest) = input.strip_prefix("add") {
+            (false, rest)
+        } else if let Some(rest) = input.strip_prefix('*') {
+            (true, rest)
+        } else if let Some(rest) = input.strip_prefix("omit") {
+            (true, rest)
+        } else if let Some(rest) = input.strip_prefix("no") {
+            (true, rest)
+        } else {
+            (false, input)
+        };
+        let (degree, accidental, rest) = parse_degree(rest).ok_or_else(|| invalid(input))?;
+        degrees.push(Degree {
+            degree,
+            accidental,
+            omit,
+        });
+        input = rest;
+    }
+}
+
+fn root_to_ascii(note: &Note) -> String {
+    let (_, accidental) = note.spelling();
+    let symbol = if accidental < 0 { "b" } else { "#" };
+    format!(
+        "{}{}",
+        note.pitchname(),
+        symbol.repeat(accidental.unsigned_abs() as usize)
+    )
+}
+
+/// A chord: a root, the intervals above it and an optional bass note,
+/// parsed from lead-sheet (`Cmaj7`, `F#m7b5`, `Bb7(#9)/D`)
+/// or Harte (`C:min7`, `A:(1,b3,5)/b3`) symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    root: Note,
+    quality: Option<&'static str>,
+    degrees: Vec<Degree>,
+    bass: Option<Note>,
+    // the degree a bass such as `/9` was written as
+    bass_number: Option<u8>,
+    intervals: Vec<(u8, i8)>,
+}
+
+impl Chord {
+    pub fn root(&self) -> &Note {
+        &self.root
+    }
+
+    pub fn bass(&self) -> Option<&Note> {
+        self.bass.as_ref()
+    }
+
+    /// Harte shorthand of the quality, such as `min7`,
+    /// `None` for a bare interval list like `C:(1,3,5)`.
+    pub fn quality(&self) -> Option<&'static str> {
+        self.quality
+    }
+
+    /// Semitones above the root of each chord tone, in ascending order.
+    pub fn intervals(&self) -> Vec<i8> {
+        self.intervals
+            .iter()
+            .map(|&(_, semitones)| semitones)
+            .collect()
+    }
+
+    /// The chord tones with the root in (written) `octave`, each spelled
+    /// after its degree, and a bass note other than the root placed below.
+    /// Tones past octave 127 or beyond a quadruple sharp or flat are left out.
+    pub fn notes(&self, octave: i8) -> Vec<Note> {
+        let (letter, accidental) = self.root.spelling();
+        let root = Note::new(letter, accidental, octave, 0);
+        self.voice(&root)
+    }
+
+    /// MIDI numbers of the chord tones, with the root in the octave it was parsed in
+    /// (octave 0 for a bare note name, as in librosa).
+    pub fn midi(&self) -> Vec<f32> {
+        let octave = self.root.octave().unwrap_or(0);
+        self.voice(&self.root.clone().with_octave(octave))
+            .iter()
+            .map(|note| note.pitch_f32())
+            .collect()
+    }
+
+    fn voice(&self, root: &Note) -> Vec<Note> {
+        let mut notes: Vec<_> = self
+            .intervals
+            .iter()
+            .filter_map(|&(degree, semitones)| root.above(degree, semitones))
+            .collect();
+        if let Some(bass) = &self.bass {
+            let below = (root.pitch_f32() - bass.pitch_f32()).rem_euclid(12.);
+            if below != 0. {
+                let (letter, accidental) = bass.spelling();
+                let pitch = root.pitch_f32() - below;
+                let natural = letter.pitch() + accidental;
+                let octave = (pitch as i32 - natural as i32).div_euclid(12) - 1;
+                if let Ok(octave) = i8::try_from(octave) {
+                    notes.insert(0, Note::new(letter, accidental, octave, 0));
+                }
+            }
+        }
+        notes
+    }
+
+    /// The bass as a degree above the root, such as `b3` for `C/Eb`.
+    fn bass_degree(&self) -> Option<String> {
+        let bass = self.bass.as_ref()?;
+        let (root_letter, _) = self.root.spelling();
+        let (bass_letter, _) = bass.spelling();
+        let degree = self.bass_number.map_or(
+            (bass_letter.index() + 7 - root_letter.index()) % 7 + 1,
+            usize::from,
+        );
+        let semitones = (bass.pitch_f32() - self.root.pitch_f32()).rem_euclid(12.) as i8;
+        let mut accidental = semitones - DEGREE_TO_SEMITONES[degree - 1];
+        if accidental > 6 {
+            accidental -= 12;
+        } else if accidental < -6 {
+            accidental += 12;
+        }
+        let degree = Degree {
+            degree: degree as u8,
+            accidental,
+            omit: false,
+        };
+        Some(format!("{}{}", degree.accidental_str(), degree.degree))
+    }
+
+    /// Format as a Harte symbol, such as `Bb:7(#9)/3`.
+    pub fn to_harte(&self) -> String {
+        let mut symbol = format!(
+            "{}:{}",
+            root_to_ascii(&self.root),
+            self.quality.unwrap_or("")
+        );
+        if !self.degrees.is_empty() {
+            let degrees: Vec<_> = self
+                .degrees
+                .iter()
+                .map(|degree| {
+                    let omit = if degree.omit { "*" } else { "" };
+                    format!("{}{}{}", omit, degree.accidental_str(), degree.degree)
+                })
+                .collect();
+            symbol.push_str(&format!("({})", degrees.join(",")));
+        }
+        if let Some(bass) = self.bass_degree() {
+            symbol.push('/');
+            symbol.push_str(&bass);
+        }
+        symbol
+    }
+}
+
+impl FromStr for Chord {
+    type Err = ChordParseError;
+    fn from_str(input: &str) -> Result<Chord, ChordParseError> {
+        let options = ParseOptions::librosa();
+        let root_len = input
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| !matches!(c, '#' | '♯' | 'b' | '♭' | '𝄪' | '𝄫'))
+            .map_or(input.len(), |(i, _)| i);
+        let root = Note::parse_with(&input[..root_len], &options).map_err(ChordParseError::Root)?;
+        let rest = &input[root_len..];
+
+        let (body, bass) = match rest.rfind('/') {
+            Some(slash) => (&rest[..slash], Some(&rest[slash + 1..])),
+            None => (rest, None),
+        };
+
+        let (quality, degrees) = if let Some(harte) = body.strip_prefix(':') {
+            let shorthand = &harte[..harte.find('(').unwrap_or(harte.len())];
+            let quality = if shorthand.is_empty() && shorthand.len() < harte.len() {
+                None
+            } else {
+                let name = if shorthand.is_empty() {
+                    "maj"
+                } else {
+                    shorthand
+                };
+                let quality = QUALITIES
+                    .iter()
+                    .find(|(harte, _, _)| *harte == name)
+                    .ok_or_else(|| ChordParseError::UnknownQuality(shorthand.to_string()))?;
+                Some(quality.0)
+            };
+            (quality, parse_degrees(&harte[shorthand.len()..])?)
+        } else {
+            // the longest lead-sheet spelling that matches
+            let (spelling, quality) = QUALITIES
+                .iter()
+                .map(|&(harte, lead, _)| (lead, harte))
+                .chain(ALIASES.iter().cloned())
+                .filter(|(spelling, _)| body.starts_with(spelling))
+                .max_by_key(|(spelling, _)| spelling.len())
+                .unwrap();
+            (Some(quality), parse_degrees(&body[spelling.len()..])?)
+        };
+
+        let mut intervals: Vec<(u8, i8)> = match quality {
+            Some(quality) => QUALITIES
+                .iter()
+                .find(|(harte, _, _)| *harte == quality)
+                .unwrap()
+                .2
+                .to_vec(),
+            None => vec![(1, 0)],
+        };
+        for degree in &degrees {
+            if degree.omit {
+                intervals.retain(|&(d, _)| d != degree.degree);
+            } else {
+                if degree.accidental != 0 {
+                    // an altered degree replaces the unaltered one
+                    let natural = DEGREE_TO_SEMITONES[degree.degree as usize - 1];
+                    intervals.retain(|&interval| interval != (degree.degree, natural));
+                }
+                if !intervals.contains(&(degree.degree, degree.semitones())) {
+                    intervals.push((degree.degree, degree.semitones()));
+                }
+            }
+        }
+        intervals.sort_by_key(|&(degree, semitones)| (semitones, degree));
+
+        let bass_number = bass.and_then(parse_degree).map(|(degree, _, _)| degree);
+        let bass = match bass {
+            None => None,
+            // a note name, `b3` being a degree
+            Some(bass) if bass.starts_with(|c: char| c.is_ascii_uppercase()) => Some(
+                Note::parse_with(bass, &options)
+                    .map_err(|_| ChordParseError::InvalidBass(bass.to_string()))?,
+            ),
+            Some(bass) => match parse_degree(bass) {
+                Some((degree, accidental, "")) => {
+                    let semitones = Degree {
+                        degree,
+                        accidental,
+                        omit: false,
+                    }
+                    .semitones();
+                    Some(
+                        root.above(degree, semitones)
+                            .ok_or_else(|| ChordParseError::InvalidBass(bass.to_string()))?,
+                    )
+                }
+                _ => return Err(ChordParseError::InvalidBass(bass.to_string())),
+            },
+        };
+
+        Ok(Chord {
+            root,
+            quality,
+            degrees,
+            bass,
+            bass_number,
+            intervals,
+        })
+    }
+}
+
+/// Formats as a lead-sheet symbol, such as `B♭7(#9)/D`,
+/// naming the quality of a Harte interval list like `A:(1,b3,5)` (`Am`).
+/// Other interval lists, such as `C:(1,3,b7)`, have no lead-sheet spelling
+/// and are formatted with [`Chord::to_harte`].
+impl std::fmt::Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let named = QUALITIES
+            .iter()
+            .find(|(_, _, intervals)| *intervals == self.intervals.as_slice());
+        if named.is_none() && self.quality.is_none() {
+            return f.write_str(&self.to_harte());
+        }
+        let (letter, accidental) = self.root.spelling();
+        write!(
+            f,
+            "{}",
+            Note::new(letter, accidental, 0, 0).without_octave()
+        )?;
+        if let Some((_, lead, _)) = named {
+            f.write_str(lead)?;
+        } else if let Some(quality) = self.quality {
+            let (_, lead, _) = QUALITIES
+                .iter()
+                .find(|(harte, _, _)| *harte == quality)
+                .unwrap();
+            f.write_str(lead)?;
+        }
+        if named.is_none() && !self.degrees.is_empty() {
+            let degrees: Vec<_> = self
+                .degrees
+                .iter()
+                .map(|degree| {
+                    let prefix = match (degree.omit, degree.accidental) {
+                        (true, _) => "no",
+                        (false, 0) => "add",
+                        _ => "",
+                    };
+                    format!("{}{}{}", prefix, degree.accidental_str(), degree.degree)
+                })
+                .collect();
+            write!(f, "({})", degrees.join(","))?;
+        }
+        if let Some(bass) = &self.bass {
+            let (letter, accidental) = bass.spelling();
+            write!(
+                f,
+                "/{}",
+                Note::new(letter, accidental, 0, 0).without_octave()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chord, ChordParseError};
+
+    fn names(chord: &Chord) -> Vec<(&'static str, i8)> {
+        chord
+            .notes(4)
+            .iter()
+            .map(|note| (note.pitchname(), note.accidental() as i8))
+            .collect()
+    }
+
+    #[test]
+    fn test_lead_sheet() {
+        let chord: Chord = "Cmaj7".parse().unwrap();
+        assert_eq!(chord.quality(), Some("maj7"));
+        assert_eq!(chord.intervals(), vec![0, 4, 7, 11]);
+        assert_eq!(chord.midi(), vec![12., 16., 19., 23.]);
+        let pitches: Vec<_> = chord.notes(4).iter().map(|note| note.pitch()).collect();
+        assert_eq!(pitches, vec![60, 64, 67, 71]);
+
+        let chord: Chord = "F#m7b5".parse().unwrap();
+        assert_eq!(chord.quality(), Some("hdim7"));
+        assert_eq!(names(&chord), vec![("F", 1), ("A", 0), ("C", 0), ("E", 0)]);
+
+        let chord: Chord = "Bb7(#9)/D".parse().unwrap();
+        assert_eq!(chord.intervals(), vec![0, 4, 7, 10, 15]);
+        assert_eq!(
+            names(&chord),
+            vec![("D", 0), ("B", -1), ("D", 0), ("F", 0), ("A", -1), ("C", 1)]
+        );
+        let pitches: Vec<_> = chord.notes(3).iter().map(|note| note.pitch()).collect();
+        assert_eq!(pitches, vec![50, 58, 62, 65, 68, 73]);
+
+        let chord: Chord = "C7b9#11".parse().unwrap();
+        assert_eq!(chord.intervals(), vec![0, 4, 7, 10, 13, 18]);
+        let chord: Chord = "Cadd9".parse().unwrap();
+        assert_eq!(chord.intervals(), vec![0, 4, 7, 14]);
+        let chord: Chord = "Ebm".parse().unwrap();
+        assert_eq!(chord.quality(), Some("min"));
+        assert_eq!(names(&chord), vec![("E", -1), ("G", -1), ("B", -1)]);
+        let chord: Chord = "C7b5".parse().unwrap();
+        assert_eq!(chord.intervals(), vec![0, 4, 6, 10]);
+        let chord: Chord = "G".parse().unwrap();
+        assert_eq!(chord.intervals(), vec![0, 4, 7]);
+    }
+
+    #[test]
+    fn test_harte() {
+        let chord: Chord = "C:min7".parse().unwrap();
+        assert_eq!(chord.quality(), Some("min7"));
+        assert_eq!(chord.intervals(), vec![0, 3, 7, 10]);
+        let chord: Chord = "A:(1,b3,5)/b3".parse().unwrap();
+        assert_eq!(chord.quality(), None);
+        assert_eq!(chord.intervals(), vec![0, 3, 7]);
+        assert_eq!(chord.bass().unwrap().pitchname(), "C");
+        let chord: Chord = "D:maj(*5)/3".parse().unwrap();
+        assert_eq!(chord.intervals(), vec![0, 4]);
+        assert_eq!(chord.bass().unwrap().pitchname(), "F");
+        assert_eq!(chord.bass().unwrap().accidental(), 1.);
+        let chord: Chord = "Eb:maj7/7".parse().unwrap();
+        assert_eq!(chord.bass().unwrap().pitchname(), "D");
+        let chord: Chord = "C:maj/9".parse().unwrap();
+        assert_eq!(chord.bass().unwrap().pitchname(), "D");
+        let pitches: Vec<_> = chord.notes(4).iter().map(|note| note.pitch()).collect();
+        assert_eq!(pitches, vec![50, 60, 64, 67]);
+    }
+
+    #[test]
+    fn test_format() {
+        for (symbol, lead, harte) in &[
+            ("Cmaj7", "Cmaj7", "C:maj7"),
+            ("F#m7b5", "F♯m7b5", "F#:hdim7"),
+            ("Bb7(#9)/D", "B♭7(#9)/D", "Bb:7(#9)/3"),
+            ("C:min7", "Cm7", "C:min7"),
+            ("Cadd9", "C(add9)", "C:maj(9)"),
+            ("A:(1,b3,5)/b3", "Am/C", "A:(1,b3,5)/b3"),
+            ("C:(1,3,b7)", "C:(1,3,b7)", "C:(1,3,b7)"),
+            ("C:maj/9", "C/D", "C:maj/9"),
+            ("C:(1,3,5,b7)", "C7", "C:(1,3,5,b7)"),
+            ("C:maj(b7)", "C7", "C:maj(b7)"),
+            ("Dm7(no5)", "Dm7(no5)", "D:min7(*5)"),
+        ] {
+            let chord: Chord = symbol.parse().unwrap();
+            assert_eq!(&chord.to_string(), lead, "{}", symbol);
+            assert_eq!(&chord.to_harte(), harte, "{}", symbol);
+            let again: Chord = chord.to_harte().parse().unwrap();
+            assert_eq!(again.intervals(), chord.intervals(), "{}", symbol);
+            let again: Chord = chord.to_string().parse().unwrap();
+            assert_eq!(again.intervals(), chord.intervals(), "{}", symbol);
+        }
+        let chord: Chord = "C:min7".parse().unwrap();
+        assert_eq!(chord, "Cm7".parse().unwrap());
+        assert_ne!(chord, "C:(1,b3,5,b7)".parse().unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            "H7".parse::<Chord>(),
+            Err(ChordParseError::Root(_))
+        ));
+        assert!(matches!("".parse::<Chord>(), Err(ChordParseError::Root(_))));
+        assert!(matches!(
+            "C:foo".parse::<Chord>(),
+            Err(ChordParseError::UnknownQuality(_))
+        ));
+        assert!(matches!(
+            "C7(#15)".parse::<Chord>(),
+            Err(ChordParseError::InvalidDegree(_))
+        ));
+        assert!(matches!(
+            "C7xyz".parse::<Chord>(),
+            Err(ChordParseError::InvalidDegree(_))
+        ));
+        assert!(matches!(
+            "C/X".parse::<Chord>(),
+            Err(ChordParseError::InvalidBass(_))
+        ));
+        for symbol in &[
+            format!("C7({}9)", "b".repeat(130)),
+            format!("C({}13)", "#".repeat(107)),
+            "C(#####5)".to_string(),
+        ] {
+            assert!(matches!(
+                symbol.parse::<Chord>(),
+                Err(ChordParseError::InvalidDegree(_))
+            ));
+        }
+        assert!(matches!(
+            format!("C/{}3", "b".repeat(130)).parse::<Chord>(),
+            Err(ChordParseError::InvalidBass(_))
+        ));
+    }
+}
@@ -0,0 +1,2103 @@
+use std::convert::TryFrom;
+
+use thiserror::Error;
+
+use super::converters::mela_converter::{MELAKARTA_MAP, THAAT_MAP};
+use super::converters::note_converter::{self, NoteParseError, ParseOptions};
+use super::key::Key;
+use super::note::{Letter, Note};
+
+#[derive(Debug, Error)]
+pub enum ParameterError {
+    #[error("mela={0} must be in range [1, 72]")]
+    InvalidMela(u8),
+    #[error("Unknown mela name: {0}")]
+    UnknownMela(String),
+    #[error("Unknown thaat name: {0}")]
+    UnknownThaat(String),
+    #[error("Improper key format: {0}")]
+    InvalidKey(String),
+    #[error("Unknown scale name: {0}")]
+    UnknownScale(String),
+    #[error("Scale step={0} must be in [0, 11] and above the previous step")]
+    InvalidStep(i8),
+    #[error("MIDI number {0} must be finite and in range")]
+    InvalidMidi(f32),
+    #[error("Frequency={0} must be strictly positive")]
+    InvalidFrequency(f32),
+    #[error("hop_length must be positive")]
+    InvalidHopLength,
+    #[error("At least one frequency is needed")]
+    NoFrequencies,
+    #[error("Interval={0} must be strictly positive")]
+    InvalidInterval(f32),
+    #[error("Interval={0} has no rational approximation")]
+    UnknownInterval(f32),
+}
+
+/// Convert one note name to a (fractional) MIDI number.
+///
+/// ```text
+/// >>> librosa.note_to_midi('C#3')
+/// 49
+/// >>> librosa.note_to_midi('A4+50', round_midi=False)
+/// 69.5
+/// ```
+pub fn note_to_midi(note: &str) -> Result<f32, NoteParseError> {
+    Ok(Note::parse_with(note, &ParseOptions::librosa())?.pitch_f32())
+}
+
+/// Convert a list of note names to MIDI numbers, failing on the first bad note.
+///
+/// ```text
+/// >>> librosa.note_to_midi(['C', 'E', 'G'])
+/// array([12, 16, 19])
+/// ```
+pub fn note_to_midi_many(notes: &[&str]) -> Result<Vec<f32>, NoteParseError> {
+    notes.iter().map(|note| note_to_midi(note)).collect()
+}
+
+fn accidental_to_str(accidental: i8, unicode: bool) -> &'static str {
+    if unicode {
+        return note_converter::I8_TO_ACCIDENTAL
+            [&(accidental * note_converter::QUARTER_TONES_PER_SEMITONE)];
+    }
+    match accidental {
+        2 => "##",
+        1 => "#",
+        -1 => "b",
+        -2 => "bb",
+        _ => "",
+    }
+}
+
+/// Convert a (fractional) MIDI number to a note name, spelled as in `key`.
+///
+/// The MIDI number is rounded to the nearest semitone, the remainder is
+/// reported as cents when `cents` is set. Infinite and NaN numbers are rejected.
+///
+/// ```text
+/// >>> librosa.midi_to_note(61.3, cents=True)
+/// 'C♯4+30'
+/// >>> librosa.midi_to_note(60, octave=False, unicode=False)
+/// 'C'
+/// >>> librosa.midi_to_note(70, key='F:maj')
+/// 'B♭4'
+/// ```
+pub fn midi_to_note(
+    midi: f32,
+    octave: bool,
+    cents: bool,
+    key: &Key,
+    unicode: bool,
+) -> Result<String, ParameterError> {
+    if !midi.is_finite() {
+        return Err(ParameterError::InvalidMidi(midi));
+    }
+    let note_num = midi.round_ties_even();
+    let note_cents = ((midi - note_num) * 100.).round_ties_even() as i32;
+    // saturates, so that anything too large is reported below
+    let note_num = note_num as i32;
+
+    let (letter, accidental) = key.spelling()[note_num.rem_euclid(12) as usize];
+    // octave of the spelled note, so that B♯3 is written for C4,
+    // in the range of `Note::try_from_midi` even when it is not written
+    let pitch = i32::from(letter.pitch() + accidental);
+    let note_octave = note_num
+        .checked_sub(pitch)
+        .and_then(|natural| i8::try_from(natural.div_euclid(12) - 1).ok())
+        .ok_or(ParameterError::InvalidMidi(midi))?;
+    let mut note = format!("{}{}", letter, accidental_to_str(accidental, unicode));
+    if octave {
+        note.push_str(&note_octave.to_string());
+    }
+    if cents {
+        note.push_str(&format!("{:+}", note_cents));
+    }
+    Ok(note)
+}
+
+/// Get MIDI number(s) for given frequencies, relative to the reference pitch `a4`.
+///
+/// ```text
+/// >>> librosa.hz_to_midi(440.0)
+/// 69.0
+/// ```
+pub fn hz_to_midi(hz: f32, a4: f32) -> f32 {
+    12. * (hz.log2() - a4.log2()) + 69.
+}
+
+/// Get the frequency (Hz) of a (fractional) MIDI number, relative to the reference pitch `a4`.
+///
+/// ```text
+/// >>> librosa.midi_to_hz(36)
+/// 65.406
+/// ```
+pub fn midi_to_hz(midi: f32, a4: f32) -> f32 {
+    a4 * 2_f32.powf((midi - 69.) / 12.)
+}
+
+/// Convert a frequency to a note name, see [`midi_to_note`] for the formatting options.
+///
+/// ```text
+/// >>> librosa.hz_to_note(440.0)
+/// 'A4'
+/// >>> librosa.hz_to_note(440.0 * (2.0 ** np.linspace(0, 1, 12)), octave=False)
+/// ['A', 'A♯', 'B', 'C', 'C♯', 'D', 'E', 'F', 'F♯', 'G', 'G♯', 'A']
+/// ```
+pub fn hz_to_note(
+    hz: f32,
+    a4: f32,
+    octave: bool,
+    cents: bool,
+    key: &Key,
+    unicode: bool,
+) -> Result<String, ParameterError> {
+    midi_to_note(hz_to_midi(hz, a4), octave, cents, key, unicode)
+}
+
+/// Get the frequency (Hz) of a note name, relative to the reference pitch `a4`.
+///
+/// ```text
+/// >>> librosa.note_to_hz('C')
+/// 16.351
+/// ```
+pub fn note_to_hz(note: &str, a4: f32) -> Result<f32, NoteParseError> {
+    Ok(midi_to_hz(note_to_midi(note)?, a4))
+}
+
+// Slaney's Auditory Toolbox mel scale: linear below 1kHz, logarithmic above.
+const MEL_F_MIN: f32 = 0.;
+const MEL_F_SP: f32 = 200. / 3.;
+const MEL_MIN_LOG_HZ: f32 = 1000.;
+const MEL_MIN_LOG_MEL: f32 = (MEL_MIN_LOG_HZ - MEL_F_MIN) / MEL_F_SP;
+
+fn mel_logstep() -> f32 {
+    6.4_f32.ln() / 27.
+}
+
+/// Convert Hz to Mels, with the HTK formula if `htk` is set, otherwise Slaney's.
+///
+/// ```text
+/// >>> librosa.hz_to_mel(60)
+/// 0.9
+/// >>> librosa.hz_to_mel([110, 220, 440])
+/// array([ 1.65,  3.3 ,  6.6 ])
+/// ```
+pub fn hz_to_mel(hz: f32, htk: bool) -> f32 {
+    if htk {
+        return 2595. * (1. + hz / 700.).log10();
+    }
+    if hz >= MEL_MIN_LOG_HZ {
+        MEL_MIN_LOG_MEL + (hz / MEL_MIN_LOG_HZ).ln() / mel_logstep()
+    } else {
+        (hz - MEL_F_MIN) / MEL_F_SP
+    }
+}
+
+pub fn hz_to_mel_many(hz: &[f32], htk: bool) -> Vec<f32> {
+    hz.iter().map(|&hz| hz_to_mel(hz, htk)).collect()
+}
+
+/// Convert Mels to Hz, with the HTK formula if `htk` is set, otherwise Slaney's.
+///
+/// ```text
+/// >>> librosa.mel_to_hz(3)
+/// 200.
+/// >>> librosa.mel_to_hz([1, 2, 3, 4, 5])
+/// array([  66.667,  133.333,  200.   ,  266.667,  333.333])
+/// ```
+pub fn mel_to_hz(mel: f32, htk: bool) -> f32 {
+    if htk {
+        return 700. * (10_f32.powf(mel / 2595.) - 1.);
+    }
+    if mel >= MEL_MIN_LOG_MEL {
+        MEL_MIN_LOG_HZ * (mel_logstep() * (mel - MEL_MIN_LOG_MEL)).exp()
+    } else {
+        MEL_F_MIN + MEL_F_SP * mel
+    }
+}
+
+pub fn mel_to_hz_many(mel: &[f32], htk: bool) -> Vec<f32> {
+    mel.iter().map(|&mel| mel_to_hz(mel, htk)).collect()
+}
+
+// A4 shifted by `tuning` (a fraction of a bin), so that with 12 bins per
+// octave a tuning of `0.01` is one cent, like `Note`'s cents.
+fn tuned_a4(tuning: f32, bins_per_octave: u32) -> f32 {
+    440. * 2_f32.powf(tuning / bins_per_octave as f32)
+}
+
+/// Convert frequencies (Hz) to (fractional) octave numbers, where octave 0 starts at C0.
+///
+/// ```text
+/// >>> librosa.hz_to_octs(440.0)
+/// 4.
+/// >>> librosa.hz_to_octs([32, 64, 128, 256])
+/// array([ 0.219,  1.219,  2.219,  3.219])
+/// ```
+pub fn hz_to_octs(hz: f32, tuning: f32, bins_per_octave: u32) -> f32 {
+    (hz / (tuned_a4(tuning, bins_per_octave) / 16.)).log2()
+}
+
+/// Convert (fractional) octave numbers to frequencies (Hz), the inverse of [`hz_to_octs`].
+///
+/// ```text
+/// >>> librosa.octs_to_hz(1)
+/// 55.
+/// >>> librosa.octs_to_hz([-2, -1, 0, 1, 2])
+/// array([   6.875,   13.75 ,   27.5  ,   55.   ,  110.   ])
+/// ```
+pub fn octs_to_hz(octs: f32, tuning: f32, bins_per_octave: u32) -> f32 {
+    (tuned_a4(tuning, bins_per_octave) / 16.) * 2_f32.powf(octs)
+}
+
+// Frames are centered on `n_fft / 2` when an FFT window length is given.
+fn frame_offset(n_fft: Option<usize>) -> i64 {
+    n_fft.map_or(0, |n_fft| (n_fft / 2) as i64)
+}
+
+/// Convert frame indices to audio sample indices.
+///
+/// ```text
+/// >>> librosa.frames_to_samples([0, 1, 2, 3], hop_length=512)
+/// array([   0,  512, 1024, 1536])
+/// ```
+pub fn frames_to_samples(frames: i64, hop_length: usize, n_fft: Option<usize>) -> i64 {
+    frames * hop_length as i64 + frame_offset(n_fft)
+}
+
+/// Convert sample indices into the frame indices containing them,
+/// failing for a `hop_length` of 0.
+///
+/// ```text
+/// >>> librosa.samples_to_frames([0, 512, 1024, 1536], hop_length=512)
+/// array([0, 1, 2, 3])
+/// ```
+pub fn samples_to_frames(
+    samples: i64,
+    hop_length: usize,
+    n_fft: Option<usize>,
+) -> Result<i64, ParameterError> {
+    if hop_length == 0 {
+        return Err(ParameterError::InvalidHopLength);
+    }
+    Ok((samples - frame_offset(n_fft)).div_euclid(hop_length as i64))
+}
+
+/// Convert frame indices to time (seconds).
+///
+/// ```text
+/// >>> librosa.frames_to_time([0, 1, 2], sr=22050, hop_length=512)
+/// array([0.   , 0.023, 0.046])
+/// ```
+pub fn frames_to_time(frames: i64, sr: u32, hop_length: usize, n_fft: Option<usize>) -> f32 {
+    samples_to_time(frames_to_samples(frames, hop_length, n_fft), sr)
+}
+
+/// Convert time (seconds) to the index of the frame containing it,
+/// failing for a `hop_length` of 0.
+///
+/// ```text
+/// >>> librosa.time_to_frames(1.0, sr=22050, hop_length=512)
+/// 43
+/// ```
+pub fn time_to_frames(
+    time: f32,
+    sr: u32,
+    hop_length: usize,
+    n_fft: Option<usize>,
+) -> Result<i64, ParameterError> {
+    samples_to_frames(time_to_samples(time, sr), hop_length, n_fft)
+}
+
+/// Convert time (seconds) to sample indices, truncating like librosa.
+///
+/// ```text
+/// >>> librosa.time_to_samples(np.arange(0, 1, 0.1), sr=22050)
+/// array([    0,  2205,  4410,  6615,  8820, 11025, 13230, 15435, 17640, 19845])
+/// ```
+pub fn time_to_samples(time: f32, sr: u32) -> i64 {
+    (time * sr as f32) as i64
+}
+
+/// Convert sample indices to time (seconds).
+///
+/// ```text
+/// >>> librosa.samples_to_time(22050, sr=22050)
+/// 1.0
+/// ```
+pub fn samples_to_time(samples: i64, sr: u32) -> f32 {
+    samples as f32 / sr as f32
+}
+
+/// Convert block indices to the index of their first frame.
+///
+/// ```text
+/// >>> librosa.blocks_to_frames(3, block_length=16)
+/// 48
+/// ```
+pub fn blocks_to_frames(blocks: i64, block_length: usize) -> i64 {
+    blocks * block_length as i64
+}
+
+/// Convert block indices to the index of their first sample.
+///
+/// ```text
+/// >>> librosa.blocks_to_samples(3, block_length=16, hop_length=512)
+/// 24576
+/// ```
+pub fn blocks_to_samples(blocks: i64, block_length: usize, hop_length: usize) -> i64 {
+    frames_to_samples(blocks_to_frames(blocks, block_length), hop_length, None)
+}
+
+/// Convert block indices to the time (seconds) of their first sample.
+///
+/// ```text
+/// >>> librosa.blocks_to_time(3, block_length=16, hop_length=512, sr=22050)
+/// 1.1145578
+/// ```
+pub fn blocks_to_time(blocks: i64, block_length: usize, hop_length: usize, sr: u32) -> f32 {
+    samples_to_time(blocks_to_samples(blocks, block_length, hop_length), sr)
+}
+
+/// Something that names a frequency: plain Hz, or a [`Note`] tuned to the reference pitch `a4`.
+pub trait ToHz {
+    fn to_hz(&self, a4: f32) -> f32;
+}
+
+impl ToHz for f32 {
+    fn to_hz(&self, _a4: f32) -> f32 {
+        *self
+    }
+}
+
+impl ToHz for Note {
+    fn to_hz(&self, a4: f32) -> f32 {
+        self.hz(a4)
+    }
+}
+
+impl<T: ToHz + ?Sized> ToHz for &T {
+    fn to_hz(&self, a4: f32) -> f32 {
+        (**self).to_hz(a4)
+    }
+}
+
+fn linspace(start: f32, stop: f32, num: usize) -> Vec<f32> {
+    match num {
+        0 => return Vec::new(),
+        1 => return vec![start],
+        _ => {}
+    }
+    let step = (stop - start) / (num - 1) as f32;
+    (0..num).map(|i| start + step * i as f32).collect()
+}
+
+/// Frequencies (Hz) of the bins of a real-valued FFT.
+///
+/// ```text
+/// >>> librosa.fft_frequencies(sr=22050, n_fft=16)
+/// array([     0.   ,   1378.125,   2756.25 ,   4134.375,
+///          5512.5  ,   6890.625,   8268.75 ,   9646.875,  11025.   ])
+/// ```
+pub fn fft_frequencies(sr: u32, n_fft: usize) -> Vec<f32> {
+    (0..=n_fft / 2)
+        .map(|k| k as f32 * sr as f32 / n_fft as f32)
+        .collect()
+}
+
+/// Center frequencies (Hz) of constant-Q bins, starting at `fmin` in Hz or as a [`Note`]
+/// tuned to the reference pitch `a4`.
+///
+/// ```text
+/// >>> librosa.cqt_frequencies(24, fmin=librosa.note_to_hz('C1'))
+/// array([  32.703,   34.648,   36.708,   38.891,   41.203,   43.654,
+///          46.249,   48.999,   51.913,   55.   ,   58.27 ,   61.735,
+///          65.406,   69.296,   73.416,   77.782,   82.407,   87.307,
+///          92.499,   97.999,  103.826,  110.   ,  116.541,  123.471])
+/// ```
+pub fn cqt_frequencies<F: ToHz>(
+    n_bins: usize,
+    fmin: F,
+    a4: f32,
+    bins_per_octave: u32,
+    tuning: f32,
+) -> Vec<f32> {
+    let fmin = fmin.to_hz(a4) * 2_f32.powf(tuning / bins_per_octave as f32);
+    (0..n_bins)
+        .map(|k| fmin * 2_f32.powf(k as f32 / bins_per_octave as f32))
+        .collect()
+}
+
+/// Frequencies (Hz) of `n_mels` bins evenly spaced on the mel scale between `fmin` and `fmax`.
+///
+/// ```text
+/// >>> librosa.mel_frequencies(n_mels=40)
+/// array([     0.   ,     85.317,    170.635,    255.952,
+///           341.269,    426.586,    511.904,    597.221,
+///           682.538,    767.855,    853.173,    938.49 ,
+///          1024.856,   1119.114,   1222.042,   1334.436,
+///          ...
+///         11025.   ])
+/// ```
+pub fn mel_frequencies(n_mels: usize, fmin: f32, fmax: f32, htk: bool) -> Vec<f32> {
+    let mels = linspace(hz_to_mel(fmin, htk), hz_to_mel(fmax, htk), n_mels);
+    mel_to_hz_many(&mels, htk)
+}
+
+/// Frequencies (BPM) of the lags of an onset autocorrelation, the first bin is infinite.
+///
+/// ```text
+/// >>> librosa.tempo_frequencies(384)
+/// array([      inf,  2583.984,  1291.992, ...,     6.782,
+///            6.764,     6.747])
+/// ```
+pub fn tempo_frequencies(n_bins: usize, hop_length: usize, sr: u32) -> Vec<f32> {
+    (0..n_bins)
+        .map(|lag| {
+            if lag == 0 {
+                f32::INFINITY
+            } else {
+                60. * sr as f32 / (hop_length * lag) as f32
+            }
+        })
+        .collect()
+}
+
+/// Frequency weighting curves, see [`frequency_weighting`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    A,
+    B,
+    C,
+    D,
+    Z,
+}
+
+fn clip_weights(weights: impl Iterator<Item = f32>, min_db: Option<f32>) -> Vec<f32> {
+    match min_db {
+        Some(min_db) => weights.map(|w| w.max(min_db)).collect(),
+        None => weights.collect(),
+    }
+}
+
+/// A-weighting (dB) of a set of frequencies, clipped below at `min_db`.
+///
+/// ```text
+/// >>> librosa.A_weighting([10, 100, 1000, 10000])
+/// array([-70.43 , -19.142,   0.   ,  -2.491])
+/// ```
+pub fn a_weighting(frequencies: &[f32], min_db: Option<f32>) -> Vec<f32> {
+    let c = [
+        12194.217_f32.powi(2),
+        20.598_997_f32.powi(2),
+        107.652_65_f32.powi(2),
+        737.862_23_f32.powi(2),
+    ];
+    let weights = frequencies.iter().map(|f| {
+        let f_sq = f * f;
+        2. + 20.
+            * (c[0].log10() + 2. * f_sq.log10()
+                - (f_sq + c[0]).log10()
+                - (f_sq + c[1]).log10()
+                - 0.5 * (f_sq + c[2]).log10()
+                - 0.5 * (f_sq + c[3]).log10())
+    });
+    clip_weights(weights, min_db)
+}
+
+/// B-weighting (dB) of a set of frequencies, clipped below at `min_db`.
+///
+/// ```text
+/// >>> librosa.B_weighting([10, 100, 1000, 10000])
+/// array([-38.239,  -5.647,   0.   ,  -4.298])
+/// ```
+pub fn b_weighting(frequencies: &[f32], min_db: Option<f32>) -> Vec<f32> {
+    let c = [
+        12194.217_f32.powi(2),
+        20.598_997_f32.powi(2),
+        158.489_32_f32.powi(2),
+    ];
+    let weights = frequencies.iter().map(|f| {
+        let f_sq = f * f;
+        0.17 + 20.
+            * (c[0].log10() + 1.5 * f_sq.log10()
+                - (f_sq + c[0]).log10()
+                - (f_sq + c[1]).log10()
+                - 0.5 * (f_sq + c[2]).log10())
+    });
+    clip_weights(weights, min_db)
+}
+
+/// C-weighting (dB) of a set of frequencies, clipped below at `min_db`.
+///
+/// ```text
+/// >>> librosa.C_weighting([10, 100, 1000, 10000])
+/// array([-14.33 ,  -0.3  ,   0.   ,  -4.405])
+/// ```
+pub fn c_weighting(frequencies: &[f32], min_db: Option<f32>) -> Vec<f32> {
+    let c = [12194.217_f32.powi(2), 20.598_997_f32.powi(2)];
+    let weights = frequencies.iter().map(|f| {
+        let f_sq = f * f;
+        0.062 + 20. * (c[0].log10() + f_sq.log10() - (f_sq + c[0]).log10() - (f_sq + c[1]).log10())
+    });
+    clip_weights(weights, min_db)
+}
+
+/// D-weighting (dB) of a set of frequencies, clipped below at `min_db`.
+///
+/// ```text
+/// >>> librosa.D_weighting([10, 100, 1000, 10000])
+/// array([-26.631,  -7.205,  -0.   ,   3.437])
+/// ```
+pub fn d_weighting(frequencies: &[f32], min_db: Option<f32>) -> Vec<f32> {
+    let c = [
+        8.304_63e-3_f32.powi(2),
+        1018.7_f32.powi(2),
+        1039.6_f32.powi(2),
+        3136.5_f32.powi(2),
+        3424_f32.powi(2),
+        282.7_f32.powi(2),
+        1160_f32.powi(2),
+    ];
+    let weights = frequencies.iter().map(|f| {
+        let f_sq = f * f;
+        20. * (0.5 * f_sq.log10() - c[0].log10()
+            + 0.5
+                * (((c[1] - f_sq).powi(2) + c[2] * f_sq).log10()
+                    - ((c[3] - f_sq).powi(2) + c[4] * f_sq).log10()
+                    - (c[5] + f_sq).log10()
+                    - (c[6] + f_sq).log10()))
+    });
+    clip_weights(weights, min_db)
+}
+
+/// Z-weighting, which is flat: every frequency gets 0dB.
+pub fn z_weighting(frequencies: &[f32], _min_db: Option<f32>) -> Vec<f32> {
+    vec![0.; frequencies.len()]
+}
+
+/// Weighting (dB) of a set of frequencies by the curve `kind`, clipped below at `min_db`.
+///
+/// ```text
+/// >>> librosa.frequency_weighting([100, 1000], kind='A')
+/// array([-19.142,   0.   ])
+/// ```
+pub fn frequency_weighting(frequencies: &[f32], kind: Weighting, min_db: Option<f32>) -> Vec<f32> {
+    match kind {
+        Weighting::A => a_weighting(frequencies, min_db),
+        Weighting::B => b_weighting(frequencies, min_db),
+        Weighting::C => c_weighting(frequencies, min_db),
+        Weighting::D => d_weighting(frequencies, min_db),
+        Weighting::Z => z_weighting(frequencies, min_db),
+    }
+}
+
+/// One row of [`frequency_weighting`] per curve in `kinds`.
+///
+/// ```text
+/// >>> librosa.multi_frequency_weighting([100, 1000], kinds='ZAC')
+/// array([[  0.   ,   0.   ],
+///        [-19.142,   0.   ],
+///        [ -0.3  ,   0.   ]])
+/// ```
+pub fn multi_frequency_weighting(
+    frequencies: &[f32],
+    kinds: &[Weighting],
+    min_db: Option<f32>,
+) -> Vec<Vec<f32>> {
+    kinds
+        .iter()
+        .map(|&kind| frequency_weighting(frequencies, kind, min_db))
+        .collect()
+}
+
+const SVARA_NAMES_H: [&str; 12] = [
+    "Sa", "re", "Re", "ga", "Ga", "ma", "Ma", "Pa", "dha", "Dha", "ni", "Ni",
+];
+
+// Mark svaras of the upper (dot above) and lower (dot below) octave around Sa.
+fn svara_octave(svara: &str, svara_num: i32, unicode: bool) -> String {
+    let (above, below) = if unicode {
+        ("\u{307}", "\u{323}")
+    } else {
+        ("'", ",")
+    };
+    let mark = match svara_num {
+        12..=23 => above,
+        -12..=-1 => below,
+        _ => return svara.to_string(),
+    };
+    if unicode {
+        let mut chars = svara.chars();
+        let first = chars.next().unwrap_or_default();
+        format!("{}{}{}", first, mark, chars.as_str())
+    } else {
+        format!("{}{}", svara, mark)
+    }
+}
+
+/// Convert MIDI numbers to Hindustani svara, relative to the MIDI number `sa`.
+///
+/// ```text
+/// >>> librosa.midi_to_svara_h([60, 61, 62], Sa=60)
+/// ['S', 'r', 'R']
+/// >>> librosa.midi_to_svara_h([60, 61, 62], Sa=60, abbr=False)
+/// ['Sa', 're', 'Re']
+/// >>> librosa.midi_to_svara_h([72, 73, 48], Sa=60, octave=True)
+/// ['Ṡ', 'ṙ', 'Ṣ']
+/// ```
+pub fn midi_to_svara_h(midi: f32, sa: f32, abbr: bool, octave: bool, unicode: bool) -> String {
+    let svara_num = (midi - sa).round_ties_even() as i32;
+    let svara = SVARA_NAMES_H[svara_num.rem_euclid(12) as usize];
+    let svara = if abbr { &svara[..1] } else { svara };
+    if octave {
+        svara_octave(svara, svara_num, unicode)
+    } else {
+        svara.to_string()
+    }
+}
+
+/// Convert frequencies (Hz) to Hindustani svara, relative to `sa` in Hz.
+///
+/// ```text
+/// >>> librosa.hz_to_svara_h([261/2, 261, 261 * 2], Sa=261)
+/// ['Ṣ', 'S', 'Ṡ']
+/// ```
+pub fn hz_to_svara_h(hz: f32, sa: f32, abbr: bool, octave: bool, unicode: bool) -> String {
+    midi_to_svara_h(
+        hz_to_midi(hz, 440.),
+        hz_to_midi(sa, 440.),
+        abbr,
+        octave,
+        unicode,
+    )
+}
+
+/// A melakarta raga, by number (1-72) or by (case-insensitive) name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mela<'a> {
+    Number(u8),
+    Name(&'a str),
+}
+
+impl From<u8> for Mela<'_> {
+    fn from(number: u8) -> Self {
+        Mela::Number(number)
+    }
+}
+
+impl<'a> From<&'a str> for Mela<'a> {
+    fn from(name: &'a str) -> Self {
+        Mela::Name(name)
+    }
+}
+
+impl Mela<'_> {
+    /// The melakarta number, in 1-72.
+    pub fn number(&self) -> Result<u8, ParameterError> {
+        match *self {
+            Mela::Number(number) if (1..=72).contains(&number) => Ok(number),
+            Mela::Number(number) => Err(ParameterError::InvalidMela(number)),
+            Mela::Name(name) => MELAKARTA_MAP
+                .get(name.to_lowercase().as_str())
+                .cloned()
+                .ok_or_else(|| ParameterError::UnknownMela(name.to_string())),
+        }
+    }
+}
+
+/// Spell the twelve Carnatic svara of the melakarta raga `mela`.
+///
+/// ```text
+/// >>> librosa.mela_to_svara(1)
+/// ['S', 'R₁', 'G₁', 'G₂', 'G₃', 'M₁', 'M₂', 'P', 'D₁', 'N₁', 'N₂', 'N₃']
+/// >>> librosa.mela_to_svara('chalanatta', abbr=False, unicode=False)
+/// ['Sa', 'Ri1', 'Ri2', 'Ri3', 'Ga3', 'Ma1', 'Ma2', 'Pa', 'Dha1', 'Dha2', 'Dha3', 'Ni3']
+/// ```
+pub fn mela_to_svara<'a, M: Into<Mela<'a>>>(
+    mela: M,
+    abbr: bool,
+    unicode: bool,
+) -> Result<Vec<String>, ParameterError> {
+    let mela_idx = mela.into().number()? - 1;
+    let lower = mela_idx % 36;
+    let upper = mela_idx % 6;
+    let svara = [
+        "Sa",
+        "Ri₁",
+        if lower < 6 { "Ga₁" } else { "Ri₂" },
+        if lower < 30 { "Ga₂" } else { "Ri₃" },
+        "Ga₃",
+        "Ma₁",
+        "Ma₂",
+        "Pa",
+        "Dha₁",
+        if upper == 0 { "Ni₁" } else { "Dha₂" },
+        if upper == 5 { "Dha₃" } else { "Ni₂" },
+        "Ni₃",
+    ];
+    Ok(svara
+        .iter()
+        .map(|s| {
+            s.chars()
+                .filter(|c| !abbr || !matches!(c, 'a' | 'h' | 'i'))
+                .map(|c| match c {
+                    '₁' if !unicode => '1',
+                    '₂' if !unicode => '2',
+                    '₃' if !unicode => '3',
+                    c => c,
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Scale degrees (semitones above Sa) of the melakarta raga `mela`.
+///
+/// ```text
+/// >>> librosa.mela_to_degrees(1)
+/// array([0, 1, 2, 5, 7, 8, 9])
+/// >>> librosa.mela_to_degrees('kanakangi')
+/// array([0, 1, 2, 5, 7, 8, 9])
+/// ```
+pub fn mela_to_degrees<'a, M: Into<Mela<'a>>>(mela: M) -> Result<[i8; 7], ParameterError> {
+    let index = mela.into().number()? - 1;
+    let (ri, ga) = match index % 36 {
+        0..=5 => (1, 2),
+        6..=11 => (1, 3),
+        12..=17 => (1, 4),
+        18..=23 => (2, 3),
+        24..=29 => (2, 4),
+        _ => (3, 4),
+    };
+    let ma = if index < 36 { 5 } else { 6 };
+    let (dha, ni) = match index % 6 {
+        0 => (8, 9),
+        1 => (8, 10),
+        2 => (8, 11),
+        3 => (9, 10),
+        4 => (9, 11),
+        _ => (10, 11),
+    };
+    Ok([0, ri, ga, ma, 7, dha, ni])
+}
+
+/// The notes of the melakarta raga `mela` from `sa` upwards, spelled with sharps.
+pub fn mela_to_notes<'a, M: Into<Mela<'a>>>(
+    mela: M,
+    sa: &Note,
+) -> Result<Vec<Note>, ParameterError> {
+    let sa = sa.pitch_f32();
+    mela_to_degrees(mela)?
+        .iter()
+        .map(|&degree| {
+            let midi = sa + degree as f32;
+            Note::try_from_midi(midi).map_err(|_| ParameterError::InvalidMidi(midi))
+        })
+        .collect()
+}
+
+/// Scale degrees (semitones above Sa) of the (case-insensitive) Hindustani `thaat`.
+///
+/// ```text
+/// >>> librosa.thaat_to_degrees('bilaval')
+/// array([ 0,  2,  4,  5,  7,  9, 11])
+/// >>> librosa.thaat_to_degrees('todi')
+/// array([ 0,  1,  3,  6,  7,  8, 11])
+/// ```
+pub fn thaat_to_degrees(thaat: &str) -> Result<[i8; 7], ParameterError> {
+    THAAT_MAP
+        .get(thaat.to_lowercase().as_str())
+        .cloned()
+        .ok_or_else(|| ParameterError::UnknownThaat(thaat.to_string()))
+}
+
+/// Names and numbers of all 72 melakarta ragas, in order.
+///
+/// ```text
+/// >>> librosa.list_mela()
+/// {'kanakangi': 1, 'ratnangi': 2, 'ganamurthi': 3, 'vanaspathi': 4, ...}
+/// ```
+pub fn list_mela() -> Vec<(&'static str, u8)> {
+    let mut melas: Vec<_> = MELAKARTA_MAP
+        .iter()
+        .map(|(&name, &number)| (name, number))
+        .collect();
+    melas.sort_by_key(|&(_, number)| number);
+    melas
+}
+
+/// Names of all 10 Hindustani thaats.
+///
+/// ```text
+/// >>> librosa.list_thaat()
+/// ['bilaval', 'khamaj', 'kafi', 'asavari', 'bhairavi', 'kalyan', 'marva', 'poorvi', 'todi', 'bhairav']
+/// ```
+pub fn list_thaat() -> Vec<&'static str> {
+    const THAATS: [&str; 10] = [
+        "bilaval", "khamaj", "kafi", "asavari", "bhairavi", "kalyan", "marva", "poorvi", "todi",
+        "bhairav",
+    ];
+    THAATS.to_vec()
+}
+
+/// Convert MIDI numbers to Carnatic svara of the melakarta raga `mela`,
+/// relative to the MIDI number `sa`.
+///
+/// ```text
+/// >>> librosa.midi_to_svara_c([60, 61, 62], Sa=60, mela=1)
+/// ['S', 'R₁', 'G₁']
+/// >>> librosa.midi_to_svara_c([60, 61, 62], Sa=60, mela=36, abbr=False, unicode=False)
+/// ['Sa', 'Ri1', 'Ri2']
+/// ```
+pub fn midi_to_svara_c<'a, M: Into<Mela<'a>>>(
+    midi: f32,
+    sa: f32,
+    mela: M,
+    abbr: bool,
+    octave: bool,
+    unicode: bool,
+) -> Result<String, ParameterError> {
+    let svara_num = (midi - sa).round_ties_even() as i32;
+    let svara_map = mela_to_svara(mela, abbr, unicode)?;
+    let svara = &svara_map[svara_num.rem_euclid(12) as usize];
+    if octave {
+        Ok(svara_octave(svara, svara_num, unicode))
+    } else {
+        Ok(svara.clone())
+    }
+}
+
+/// Convert frequencies (Hz) to Carnatic svara of the melakarta raga `mela`,
+/// relative to `sa` in Hz.
+///
+/// ```text
+/// >>> librosa.hz_to_svara_c([261/2, 261, 261 * 2], Sa=261, mela='kanakangi')
+/// ['Ṣ', 'S', 'Ṡ']
+/// ```
+pub fn hz_to_svara_c<'a, M: Into<Mela<'a>>>(
+    hz: f32,
+    sa: f32,
+    mela: M,
+    abbr: bool,
+    octave: bool,
+    unicode: bool,
+) -> Result<String, ParameterError> {
+    midi_to_svara_c(
+        hz_to_midi(hz, 440.),
+        hz_to_midi(sa, 440.),
+        mela,
+        abbr,
+        octave,
+        unicode,
+    )
+}
+
+/// Spell the 12 pitch classes (starting at C) as they appear in `key`,
+/// non-scale notes are marked with ♮ when `natural` is set.
+///
+/// ```text
+/// >>> librosa.key_to_notes('C:maj')
+/// ['C', 'C♯', 'D', 'D♯', 'E', 'F', 'F♯', 'G', 'G♯', 'A', 'A♯', 'B']
+/// >>> librosa.key_to_notes('Eb:maj')
+/// ['C', 'D♭', 'D', 'E♭', 'E', 'F', 'G♭', 'G', 'A♭', 'A', 'B♭', 'B']
+/// >>> librosa.key_to_notes('F#:maj', unicode=False)
+/// ['C', 'C#', 'D', 'D#', 'E', 'E#', 'F#', 'G', 'G#', 'A', 'A#', 'B']
+/// >>> librosa.key_to_notes('G#:min', natural=True)
+/// ['C♮', 'C♯', 'D♮', 'D♯', 'E', 'F♮', 'F♯', 'G♮', 'G♯', 'A♮', 'A♯', 'B']
+/// ```
+pub fn key_to_notes(
+    key: &str,
+    unicode: bool,
+    natural: bool,
+) -> Result<Vec<String>, ParameterError> {
+    let key: Key = key.parse()?;
+    let degrees = key.degrees();
+    Ok(key
+        .spelling()
+        .iter()
+        .zip(0..)
+        .map(|(&(letter, accidental), place)| {
+            if natural && !degrees.contains(&place) {
+                format!("{}♮", letter)
+            } else {
+                format!("{}{}", letter, accidental_to_str(accidental, unicode))
+            }
+        })
+        .collect())
+}
+
+/// Pitch classes of the scale degrees of `key`, starting at the tonic.
+///
+/// ```text
+/// >>> librosa.key_to_degrees('C:maj')
+/// array([ 0,  2,  4,  5,  7,  9, 11])
+/// >>> librosa.key_to_degrees('C#:maj')
+/// array([ 1,  3,  5,  6,  8, 10,  0])
+/// >>> librosa.key_to_degrees('A:min')
+/// array([ 9, 11,  0,  2,  4,  5,  7])
+/// ```
+pub fn key_to_degrees(key: &str) -> Result<[i8; 7], ParameterError> {
+    Ok(key.parse::<Key>()?.degrees())
+}
+
+// Balanced octave folding of an interval into [sqrt(2)/2, sqrt(2)).
+fn bo_fold(interval: f64) -> f64 {
+    interval * 2_f64.powf(-interval.log2().round_ties_even())
+}
+
+// Number of fifths (positive or negative) to come within `tolerance` of `interval`.
+fn fifth_search(interval: f64, tolerance: f64) -> i32 {
+    let log_tolerance = tolerance.log2().abs();
+    (0..32)
+        .flat_map(|power| [power, -power])
+        .find(|&fifths| bo_fold(interval / 3_f64.powi(fifths)).log2().abs() <= log_tolerance)
+        .unwrap_or(32)
+}
+
+// Largest prime a just interval may be built from.
+const PRIME_LIMIT: u64 = 23;
+
+// Best rational approximation p/q of `x` by continued fractions, keeping only
+// a ratio of PRIME_LIMIT-smooth numbers, since any `x` is close to some fraction.
+fn rationalize(x: f64) -> Option<(u64, u64)> {
+    const MAX_DENOMINATOR: u64 = 1 << 16;
+    let (mut h, mut h_prev) = (1_u64, 0_u64);
+    let (mut k, mut k_prev) = (0_u64, 1_u64);
+    let mut rest = x;
+    loop {
+        let a = rest.floor();
+        if !(0. ..u64::MAX as f64).contains(&a) {
+            return None;
+        }
+        // give up once the convergents no longer fit
+        let h_next = (a as u64).checked_mul(h)?.checked_add(h_prev)?;
+        let k_next = (a as u64).checked_mul(k)?.checked_add(k_prev)?;
+        if k_next > MAX_DENOMINATOR {
+            return None;
+        }
+        h_prev = h;
+        k_prev = k;
+        h = h_next;
+        k = k_next;
+        if (h as f64 / k as f64 - x).abs() <= x * 1e-6 {
+            return Some((h, k)).filter(|&(h, k)| is_smooth(h) && is_smooth(k));
+        }
+        rest = 1. / (rest - a);
+    }
+}
+
+// Whether `n` has no prime factor above PRIME_LIMIT.
+fn is_smooth(mut n: u64) -> bool {
+    for p in 2..=PRIME_LIMIT {
+        while n.is_multiple_of(p) {
+            n /= p;
+        }
+    }
+    n == 1
+}
+
+// Product of the prime factors of `n` above 3.
+fn strip_pythagorean(mut n: u64) -> u64 {
+    for p in &[2, 3] {
+        while n.is_multiple_of(*p) {
+            n /= p;
+        }
+    }
+    n
+}
+
+fn digits_to_script(n: u64, script: &[char; 10]) -> String {
+    n.to_string()
+        .chars()
+        .map(|d| script[d.to_digit(10).unwrap() as usize])
+        .collect()
+}
+
+/// Name of the note `fifths` perfect fifths away from `unison`.
+///
+/// ```text
+/// >>> librosa.fifths_to_note(unison='C', fifths=6)
+/// 'F♯'
+/// >>> librosa.fifths_to_note(unison='G', fifths=-3)
+/// 'B♭'
+/// ```
+pub fn fifths_to_note(unison: &Note, fifths: i32, unicode: bool) -> String {
+    // starting the circle of fifths at F makes the accidentals easy to count
+    const COFMAP: [Letter; 7] = [
+        Letter::F,
+        Letter::C,
+        Letter::G,
+        Letter::D,
+        Letter::A,
+        Letter::E,
+        Letter::B,
+    ];
+    let circle_idx = COFMAP
+        .iter()
+        .position(|&letter| letter == unison.letter())
+        .unwrap() as i32
+        + fifths;
+    let accidental = unison.accidental().round() as i32 + circle_idx.div_euclid(7);
+    let double = accidental_to_str(2 * accidental.signum() as i8, unicode);
+    let single = accidental_to_str(accidental.signum() as i8, unicode);
+    format!(
+        "{}{}{}",
+        COFMAP[circle_idx.rem_euclid(7) as usize],
+        double.repeat((accidental.abs() / 2) as usize),
+        single.repeat((accidental.abs() % 2) as usize)
+    )
+}
+
+/// Name a just `interval` above `unison` in Functional Just System notation.
+///
+/// The Pythagorean note within `tolerance` (`65 / 63` in librosa) gives the name,
+/// the remaining otonal and utonal prime factors become superscripts and subscripts,
+/// or `^` and `_` if `unicode` is not set.
+///
+/// Like librosa, this fails with [`ParameterError::UnknownInterval`] for an interval
+/// that is no ratio of 23-limit numbers, such as an equal-tempered semitone.
+///
+/// ```text
+/// >>> librosa.interval_to_fjs(3/2, unison='C')
+/// 'G'
+/// >>> librosa.interval_to_fjs(5/4, unison='A')
+/// 'C♯⁵'
+/// >>> librosa.interval_to_fjs(6/5, unison='A')
+/// 'C₅'
+/// >>> librosa.interval_to_fjs(25/14, unison='F#', unicode=False)
+/// 'E^25_7'
+/// ```
+pub fn interval_to_fjs(
+    interval: f32,
+    unison: &Note,
+    tolerance: f32,
+    unicode: bool,
+) -> Result<String, ParameterError> {
+    const SUPERSCRIPT: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    const SUBSCRIPT: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+
+    if !interval.is_finite() || interval <= 0. {
+        return Err(ParameterError::InvalidInterval(interval));
+    }
+    let fifths = fifth_search(interval as f64, tolerance as f64);
+    let mut note = fifths_to_note(unison, fifths, unicode);
+
+    let (numerator, denominator) =
+        rationalize(interval as f64).ok_or(ParameterError::UnknownInterval(interval))?;
+    let otonal = strip_pythagorean(numerator);
+    let utonal = strip_pythagorean(denominator);
+    if otonal > 1 {
+        if unicode {
+            note.push_str(&digits_to_script(otonal, &SUPERSCRIPT));
+        } else {
+            note.push_str(&format!("^{}", otonal));
+        }
+    }
+    if utonal > 1 {
+        if unicode {
+            note.push_str(&digits_to_script(utonal, &SUBSCRIPT));
+        } else {
+            note.push_str(&format!("_{}", utonal));
+        }
+    }
+    Ok(note)
+}
+
+/// Name frequencies (Hz) in Functional Just System notation, as intervals above `fmin`.
+///
+/// `fmin` defaults to the lowest frequency, and `unison` to the note nearest to `fmin`.
+///
+/// ```text
+/// >>> librosa.hz_to_fjs([55, 66, 88], unicode=True)
+/// ['A', 'C₅', 'F₅']
+/// >>> librosa.hz_to_fjs([55, 66, 88], fmin=55, unison='G')
+/// ['G', 'B♭₅', 'E♭₅']
+/// ```
+pub fn hz_to_fjs(
+    frequencies: &[f32],
+    fmin: Option<f32>,
+    unison: Option<&Note>,
+    unicode: bool,
+) -> Result<Vec<String>, ParameterError> {
+    let invalid = |hz: f32| !hz.is_finite() || hz <= 0.;
+    if let Some(&hz) = frequencies.iter().find(|&&hz| invalid(hz)) {
+        return Err(ParameterError::InvalidFrequency(hz));
+    }
+    let fmin = match fmin {
+        Some(fmin) if invalid(fmin) => return Err(ParameterError::InvalidFrequency(fmin)),
+        Some(fmin) => fmin,
+        None => frequencies
+            .iter()
+            .cloned()
+            .reduce(f32::min)
+            .ok_or(ParameterError::NoFrequencies)?,
+    };
+    let nearest;
+    let unison = match unison {
+        Some(unison) => unison,
+        None => {
+            nearest = Note::try_from_hz(fmin, 440.)
+                .map_err(|_| ParameterError::InvalidFrequency(fmin))?;
+            &nearest
+        }
+    };
+    frequencies
+        .iter()
+        .map(|hz| interval_to_fjs(hz / fmin, unison, 65. / 63., unicode))
+        .collect()
+}
+
+// Fold log2 ratios into one octave, sort them if asked, and return them
+// either as log2 values or as ratios.
+fn fold_intervals(log_ratios: impl Iterator<Item = f64>, sort: bool, log2: bool) -> Vec<f32> {
+    let mut log_ratios: Vec<f64> = log_ratios.map(|r| r - r.floor()).collect();
+    if sort {
+        log_ratios.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+    log_ratios
+        .into_iter()
+        .map(|r| if log2 { r as f32 } else { 2_f64.powf(r) as f32 })
+        .collect()
+}
+
+/// One octave of Pythagorean intervals, generated by stacking perfect fifths.
+///
+/// ```text
+/// >>> librosa.pythagorean_intervals(bins_per_octave=7)
+/// array([1.      , 1.125   , 1.265625, 1.423828, 1.5     , 1.6875  , 1.898438])
+/// ```
+pub fn pythagorean_intervals(bins_per_octave: usize, sort: bool, log2: bool) -> Vec<f32> {
+    let log3 = 3_f64.log2();
+    fold_intervals(
+        (0..bins_per_octave).map(|power| power as f64 * log3),
+        sort,
+        log2,
+    )
+}
+
+// Harmonic distance between two intervals given as exponents of `primes`.
+fn harmonic_distance(logs: &[f64], a: &[i32], b: &[i32]) -> f64 {
+    a.iter()
+        .zip(b)
+        .zip(logs)
+        .map(|((a, b), log)| (a - b).abs() as f64 * log)
+        .sum()
+}
+
+/// One octave of intervals generated by the `primes` (excluding 2), grown one
+/// at a time by picking the interval closest in harmonic distance to those already chosen.
+///
+/// ```text
+/// >>> librosa.plimit_intervals(primes=[3, 5])
+/// array([1.      , 1.066667, 1.125   , 1.2     , 1.25    , 1.333333,
+///        1.40625 , 1.5     , 1.6     , 1.666667, 1.8     , 1.875   ])
+/// ```
+pub fn plimit_intervals(
+    primes: &[u32],
+    bins_per_octave: usize,
+    sort: bool,
+    log2: bool,
+) -> Vec<f32> {
+    let logs: Vec<f64> = primes.iter().map(|&p| (p as f64).log2()).collect();
+    let unison = vec![0; primes.len()];
+
+    // each prime and its reciprocal
+    let seeds: Vec<Vec<i32>> = (0..primes.len())
+        .flat_map(|i| {
+            [1, -1].iter().map(move |&sign| {
+                let mut seed = vec![0; primes.len()];
+                seed[i] = sign;
+                seed
+            })
+        })
+        .collect();
+
+    let mut intervals = vec![unison.clone()];
+    let mut frontier = seeds.clone();
+    while intervals.len() < bins_per_octave && !frontier.is_empty() {
+        let scores: Vec<(f64, f64)> = frontier
+            .iter()
+            .map(|point| {
+                let distance: f64 = intervals
+                    .iter()
+                    .map(|interval| harmonic_distance(&logs, point, interval))
+                    .sum();
+                (distance, harmonic_distance(&logs, point, &unison))
+            })
+            .collect();
+        // ties go to the simplest interval, then to the earliest found
+        let mut best = 0;
+        for (i, &(distance, height)) in scores.iter().enumerate() {
+            let (best_distance, best_height) = scores[best];
+            if distance < best_distance - 1e-9
+                || ((distance - best_distance).abs() <= 1e-9 && height < best_height - 1e-9)
+            {
+                best = i;
+            }
+        }
+
+        let point = frontier.remove(best);
+        for seed in &seeds {
+            let next: Vec<i32> = point.iter().zip(seed).map(|(p, s)| p + s).collect();
+            if !intervals.contains(&next) && !frontier.contains(&next) {
+                frontier.push(next);
+            }
+        }
+        intervals.push(point);
+    }
+
+    fold_intervals(
+        intervals.iter().map(|powers| {
+            powers
+                .iter()
+                .zip(&logs)
+                .map(|(&power, log)| power as f64 * log)
+                .sum()
+        }),
+        sort,
+        log2,
+    )
+}
+
+/// The interval ratios of one octave, see [`interval_frequencies`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Intervals<'a> {
+    /// Equal temperament
+    Equal,
+    /// Stacked perfect fifths, see [`pythagorean_intervals`]
+    Pythagorean,
+    /// 3-limit just intonation, see [`plimit_intervals`]
+    Ji3,
+    /// 5-limit just intonation
+    Ji5,
+    /// 7-limit just intonation
+    Ji7,
+    /// Explicit ratios, one per bin of the octave
+    Ratios(&'a [f32]),
+}
+
+/// Frequencies (Hz) of `n_bins` starting at `fmin`, in Hz or as a [`Note`] tuned to
+/// the reference pitch `a4`, tiling the octave `intervals` upwards.
+///
+/// `bins_per_octave` applies to all but [`Intervals::Ratios`], and `tuning`
+/// (a fraction of a bin) only to [`Intervals::Equal`], as in librosa.
+///
+/// ```text
+/// >>> librosa.interval_frequencies(24, fmin=55, intervals="ji5", bins_per_octave=12)
+/// array([ 55.   ,  58.667,  61.875,  66.   ,  68.75 ,  73.333,  77.344,
+///         82.5  ,  88.   ,  91.667,  99.   , 103.125, 110.   , 117.333,
+///        123.75 , 132.   , 137.5  , 146.667, 154.688, 165.   , 176.   ,
+///        183.333, 198.   , 206.25 ])
+/// ```
+pub fn interval_frequencies<F: ToHz>(
+    n_bins: usize,
+    fmin: F,
+    a4: f32,
+    intervals: Intervals,
+    bins_per_octave: usize,
+    tuning: f32,
+    sort: bool,
+) -> Vec<f32> {
+    let ratios = match intervals {
+        Intervals::Equal => (0..bins_per_octave)
+            .map(|bin| 2_f32.powf((tuning + bin as f32) / bins_per_octave as f32))
+            .collect(),
+        Intervals::Pythagorean => pythagorean_intervals(bins_per_octave, sort, false),
+        Intervals::Ji3 => plimit_intervals(&[3], bins_per_octave, sort, false),
+        Intervals::Ji5 => plimit_intervals(&[3, 5], bins_per_octave, sort, false),
+        Intervals::Ji7 => plimit_intervals(&[3, 5, 7], bins_per_octave, sort, false),
+        Intervals::Ratios(ratios) => ratios.to_vec(),
+    };
+    if ratios.is_empty() {
+        return Vec::new();
+    }
+
+    let fmin = fmin.to_hz(a4);
+    let mut frequencies: Vec<f32> = (0..)
+        .flat_map(|octave| ratios.iter().map(move |ratio| 2_f32.powi(octave) * ratio))
+        .take(n_bins)
+        .map(|ratio| ratio * fmin)
+        .collect();
+    if sort {
+        frequencies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+    frequencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_to_midi() {
+        assert_eq!(note_to_midi("C").unwrap(), 12.);
+        assert_eq!(note_to_midi("C#3").unwrap(), 49.);
+        assert_eq!(note_to_midi("Bb-1").unwrap(), 10.);
+        assert_eq!(note_to_midi("A4+50").unwrap(), 69.5);
+        assert_eq!(note_to_midi("a!4").unwrap(), 68.);
+        assert_eq!(
+            note_to_midi_many(&["C", "E", "G"]).unwrap(),
+            vec![12., 16., 19.]
+        );
+        assert!(note_to_midi_many(&["C", "X", "G"]).is_err());
+    }
+
+    #[test]
+    fn test_midi_to_note() {
+        assert_eq!(
+            midi_to_note(0., true, false, &Key::default(), true).unwrap(),
+            "C-1"
+        );
+        assert_eq!(
+            midi_to_note(37., true, false, &Key::default(), true).unwrap(),
+            "C♯2"
+        );
+        assert_eq!(
+            midi_to_note(37., true, false, &Key::default(), false).unwrap(),
+            "C#2"
+        );
+        assert_eq!(
+            midi_to_note(-2., true, false, &Key::default(), true).unwrap(),
+            "A♯-2"
+        );
+        assert_eq!(
+            midi_to_note(104.7, true, false, &Key::default(), true).unwrap(),
+            "A7"
+        );
+        assert_eq!(
+            midi_to_note(104.7, true, true, &Key::default(), true).unwrap(),
+            "A7-30"
+        );
+        assert_eq!(
+            midi_to_note(61.3, true, true, &Key::default(), true).unwrap(),
+            "C♯4+30"
+        );
+        assert_eq!(
+            midi_to_note(60., true, true, &Key::default(), true).unwrap(),
+            "C4+0"
+        );
+        assert_eq!(
+            midi_to_note(60., false, false, &Key::default(), true).unwrap(),
+            "C"
+        );
+        for midi in &[
+            f32::NAN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::MIN,
+            f32::MAX,
+            1e6,
+        ] {
+            assert!(matches!(
+                midi_to_note(*midi, true, false, &Key::default(), true),
+                Err(ParameterError::InvalidMidi(_))
+            ));
+        }
+        assert!(midi_to_note(f32::MAX, false, false, &Key::default(), true).is_err());
+        assert_eq!(
+            midi_to_note(1543., true, false, &Key::default(), true).unwrap(),
+            "G127"
+        );
+        assert!(hz_to_note(0., 440., true, false, &Key::default(), true).is_err());
+    }
+
+    #[test]
+    fn test_hz_to_midi() {
+        assert_eq!(hz_to_midi(440., 440.), 69.);
+        assert_eq!(hz_to_midi(432., 432.), 69.);
+        assert!((hz_to_midi(32.703, 440.) - 24.).abs() < 1e-3);
+        assert!((hz_to_midi(415., 440.) - 67.987).abs() < 1e-3);
+        assert!((hz_to_midi(440., 415.) - 70.013).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_midi_to_hz() {
+        assert_eq!(midi_to_hz(69., 440.), 440.);
+        assert_eq!(midi_to_hz(57., 432.), 216.);
+        assert!((midi_to_hz(36., 440.) - 65.406).abs() < 1e-3);
+        assert!((midi_to_hz(60., 415.) - 246.76).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_hz_to_note() {
+        assert_eq!(
+            hz_to_note(440., 440., true, false, &Key::default(), true).unwrap(),
+            "A4"
+        );
+        assert_eq!(
+            hz_to_note(432., 440., true, true, &Key::default(), true).unwrap(),
+            "A4-32"
+        );
+        assert_eq!(
+            hz_to_note(415., 415., true, false, &Key::default(), true).unwrap(),
+            "A4"
+        );
+        assert_eq!(
+            hz_to_note(466.16, 440., false, false, &Key::default(), false).unwrap(),
+            "A#"
+        );
+        assert!((note_to_hz("C", 440.).unwrap() - 16.352).abs() < 1e-3);
+        assert!((note_to_hz("A4", 415.).unwrap() - 415.).abs() < 1e-3);
+        assert!(note_to_hz("H4", 440.).is_err());
+    }
+
+    fn assert_all_close(actual: &[f32], expected: &[f32], tol: f32) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= tol, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_hz_to_mel() {
+        assert!((hz_to_mel(60., false) - 0.9).abs() < 1e-5);
+        assert!((hz_to_mel(2000., false) - 25.0819).abs() < 1e-4);
+        assert!((hz_to_mel(60., true) - 92.6819).abs() < 1e-3);
+        assert!((hz_to_mel(1000., true) - 999.9855).abs() < 1e-3);
+        assert_all_close(
+            &hz_to_mel_many(&[110., 220., 440.], false),
+            &[1.65, 3.3, 6.6],
+            1e-5,
+        );
+    }
+
+    #[test]
+    fn test_mel_to_hz() {
+        assert!((mel_to_hz(3., false) - 200.).abs() < 1e-4);
+        assert!((mel_to_hz(25.0819, false) - 2000.).abs() < 1e-1);
+        assert!((mel_to_hz(1000., true) - 1000.022).abs() < 1e-2);
+        assert_all_close(
+            &mel_to_hz_many(&[1., 2., 3., 4., 5.], false),
+            &[66.667, 133.333, 200., 266.667, 333.333],
+            1e-3,
+        );
+        for &htk in &[false, true] {
+            for &hz in &[0., 60., 999., 1000., 4000., 11025.] {
+                let back = mel_to_hz(hz_to_mel(hz, htk), htk);
+                assert!((back - hz).abs() <= hz * 1e-5, "{} -> {}", hz, back);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hz_to_octs() {
+        assert_eq!(hz_to_octs(440., 0., 12), 4.);
+        assert_all_close(
+            &[32., 64., 128., 256.]
+                .iter()
+                .map(|&hz| hz_to_octs(hz, 0., 12))
+                .collect::<Vec<_>>(),
+            &[0.219, 1.219, 2.219, 3.219],
+            1e-3,
+        );
+        // a tuning of half a bin moves A4 up a quarter tone
+        assert!((hz_to_octs(440., 0.5, 12) - (4. - 1. / 24.)).abs() < 1e-6);
+        assert!((hz_to_octs(440., 0.5, 24) - (4. - 1. / 48.)).abs() < 1e-6);
+        let a4: Note = "A4+50".parse().unwrap();
+        assert!((hz_to_octs(a4.hz(440.), 0.5, 12) - 4.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_octs_to_hz() {
+        assert_eq!(octs_to_hz(1., 0., 12), 55.);
+        assert_all_close(
+            &[-2., -1., 0., 1., 2.]
+                .iter()
+                .map(|&octs| octs_to_hz(octs, 0., 12))
+                .collect::<Vec<_>>(),
+            &[6.875, 13.75, 27.5, 55., 110.],
+            1e-5,
+        );
+        assert!((octs_to_hz(4., -0.2, 12) - midi_to_hz(68.8, 440.)).abs() < 1e-3);
+        for &tuning in &[-0.5, 0., 0.25] {
+            let octs = hz_to_octs(1234.5, tuning, 36);
+            assert!((octs_to_hz(octs, tuning, 36) - 1234.5).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_frames_and_samples() {
+        assert_eq!(frames_to_samples(3, 512, None), 1536);
+        assert_eq!(frames_to_samples(3, 512, Some(2048)), 2560);
+        assert_eq!(frames_to_samples(0, 512, Some(2049)), 1024);
+        assert_eq!(samples_to_frames(1536, 512, None).unwrap(), 3);
+        assert_eq!(samples_to_frames(1535, 512, None).unwrap(), 2);
+        assert_eq!(samples_to_frames(2560, 512, Some(2048)).unwrap(), 3);
+        // samples before the first frame center land in negative frames
+        assert_eq!(samples_to_frames(0, 512, Some(2048)).unwrap(), -2);
+        assert_eq!(samples_to_frames(1023, 512, Some(2048)).unwrap(), -1);
+        for frame in -4..100 {
+            let samples = frames_to_samples(frame, 256, Some(1024));
+            assert_eq!(samples_to_frames(samples, 256, Some(1024)).unwrap(), frame);
+        }
+        assert!(matches!(
+            samples_to_frames(512, 0, None),
+            Err(ParameterError::InvalidHopLength)
+        ));
+    }
+
+    #[test]
+    fn test_time() {
+        assert_eq!(samples_to_time(22050, 22050), 1.);
+        assert_eq!(time_to_samples(0.5, 22050), 11025);
+        assert_eq!(time_to_samples(0.99999, 100), 99);
+        assert_all_close(
+            &[0, 1, 2]
+                .iter()
+                .map(|&frame| frames_to_time(frame, 22050, 512, None))
+                .collect::<Vec<_>>(),
+            &[0., 0.023, 0.046],
+            1e-3,
+        );
+        assert!((frames_to_time(0, 22050, 512, Some(2048)) - 0.046).abs() < 1e-3);
+        assert_eq!(time_to_frames(1., 22050, 512, None).unwrap(), 43);
+        assert_eq!(time_to_frames(1., 22050, 512, Some(2048)).unwrap(), 41);
+        assert!(time_to_frames(1., 22050, 0, None).is_err());
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(blocks_to_frames(3, 16), 48);
+        assert_eq!(blocks_to_samples(3, 16, 512), 24576);
+        assert!((blocks_to_time(3, 16, 512, 22050) - 1.114_557_8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fft_frequencies() {
+        assert_all_close(
+            &fft_frequencies(22050, 16),
+            &[
+                0., 1378.125, 2756.25, 4134.375, 5512.5, 6890.625, 8268.75, 9646.875, 11025.,
+            ],
+            1e-3,
+        );
+        assert_eq!(fft_frequencies(22050, 2048).len(), 1025);
+        assert_eq!(fft_frequencies(8, 5), vec![0., 1.6, 3.2]);
+    }
+
+    #[test]
+    fn test_cqt_frequencies() {
+        let expected = [
+            32.703, 34.648, 36.708, 38.891, 41.203, 43.654, 46.249, 48.999, 51.913, 55., 58.27,
+            61.735, 65.406, 69.296, 73.416, 77.782, 82.407, 87.307, 92.499, 97.999, 103.826, 110.,
+            116.541, 123.471,
+        ];
+        assert_all_close(
+            &cqt_frequencies(24, 32.703_194, 440., 12, 0.),
+            &expected,
+            1e-3,
+        );
+        let c1: Note = "C1".parse().unwrap();
+        assert_all_close(&cqt_frequencies(24, &c1, 440., 12, 0.), &expected, 1e-3);
+        assert_all_close(&cqt_frequencies(24, c1, 440., 12, 0.), &expected, 1e-3);
+        let quarter_tones = cqt_frequencies(3, 440., 440., 24, 0.);
+        assert_all_close(&quarter_tones, &[440., 452.893, 466.164], 1e-3);
+        let tuned = cqt_frequencies(1, 440., 440., 12, 0.5);
+        assert_all_close(&tuned, &[452.893], 1e-3);
+        let a4: Note = "A4".parse().unwrap();
+        assert_all_close(&cqt_frequencies(1, &a4, 432., 12, 0.), &[432.], 1e-3);
+    }
+
+    #[test]
+    fn test_mel_frequencies() {
+        let mels = mel_frequencies(40, 0., 11025., false);
+        assert_eq!(mels.len(), 40);
+        assert_all_close(
+            &mels[..14],
+            &[
+                0., 85.317, 170.635, 255.952, 341.269, 426.586, 511.904, 597.221, 682.538, 767.855,
+                853.173, 938.49, 1024.856, 1119.114,
+            ],
+            1e-2,
+        );
+        assert!((mels[39] - 11025.).abs() < 1e-1);
+        assert!(mel_frequencies(0, 0., 8000., true).is_empty());
+        assert_eq!(mel_frequencies(1, 0., 8000., true), vec![0.]);
+        let mels = mel_frequencies(3, 0., 8000., true);
+        assert_all_close(
+            &mels,
+            &[0., mel_to_hz(hz_to_mel(8000., true) / 2., true), 8000.],
+            1e-1,
+        );
+    }
+
+    #[test]
+    fn test_tempo_frequencies() {
+        let tempi = tempo_frequencies(384, 512, 22050);
+        assert_eq!(tempi.len(), 384);
+        assert_eq!(tempi[0], f32::INFINITY);
+        assert_all_close(&tempi[1..3], &[2583.984, 1291.992], 1e-3);
+        assert_all_close(&tempi[381..], &[6.782, 6.764, 6.747], 1e-3);
+    }
+
+    #[test]
+    fn test_weighting() {
+        let frequencies = [10., 100., 1000., 10000.];
+        assert_all_close(
+            &a_weighting(&frequencies, None),
+            &[-70.43, -19.1424, 0.0003, -2.4914],
+            1e-3,
+        );
+        assert_all_close(
+            &b_weighting(&frequencies, None),
+            &[-38.2392, -5.6469, 0.0004, -4.2984],
+            1e-3,
+        );
+        assert_all_close(
+            &c_weighting(&frequencies, None),
+            &[-14.3299, -0.2995, 0.0001, -4.4054],
+            1e-3,
+        );
+        assert_all_close(
+            &d_weighting(&frequencies, None),
+            &[-26.6306, -7.2049, -0.0003, 3.4371],
+            1e-3,
+        );
+        assert_eq!(z_weighting(&frequencies, Some(-80.)), vec![0.; 4]);
+        assert_all_close(
+            &a_weighting(&[0., 10., 1000.], Some(-60.)),
+            &[-60., -60., 0.],
+            1e-3,
+        );
+        assert_eq!(
+            frequency_weighting(&frequencies, Weighting::C, Some(-80.)),
+            c_weighting(&frequencies, Some(-80.))
+        );
+        let weights = multi_frequency_weighting(
+            &frequencies,
+            &[Weighting::Z, Weighting::A, Weighting::C],
+            Some(-80.),
+        );
+        assert_eq!(weights.len(), 3);
+        assert_eq!(weights[0], z_weighting(&frequencies, Some(-80.)));
+        assert_eq!(weights[1], a_weighting(&frequencies, Some(-80.)));
+        assert_eq!(weights[2], c_weighting(&frequencies, Some(-80.)));
+    }
+
+    #[test]
+    fn test_midi_to_svara_h() {
+        let svara =
+            |midi: f32, abbr, octave, unicode| midi_to_svara_h(midi, 60., abbr, octave, unicode);
+        assert_eq!(svara(60., true, true, true), "S");
+        assert_eq!(svara(61., true, true, true), "r");
+        assert_eq!(svara(62., true, true, true), "R");
+        assert_eq!(svara(61., false, true, true), "re");
+        assert_eq!(svara(68., false, true, true), "dha");
+        assert_eq!(svara(72., true, true, true), "S\u{307}");
+        assert_eq!(svara(73., false, true, true), "r\u{307}e");
+        assert_eq!(svara(48., true, true, true), "S\u{323}");
+        assert_eq!(svara(71., false, true, false), "Ni");
+        assert_eq!(svara(72., false, true, false), "Sa'");
+        assert_eq!(svara(59., false, true, false), "Ni,");
+        assert_eq!(svara(72., true, false, true), "S");
+        // two octaves away from Sa there is no marking
+        assert_eq!(svara(84., true, true, true), "S");
+        assert_eq!(svara(60.4, true, true, true), "S");
+    }
+
+    #[test]
+    fn test_hz_to_svara_h() {
+        let svara: Vec<_> = [130.5, 261., 522.]
+            .iter()
+            .map(|&hz| hz_to_svara_h(hz, 261., true, true, true))
+            .collect();
+        assert_eq!(svara, vec!["S\u{323}", "S", "S\u{307}"]);
+    }
+
+    #[test]
+    fn test_mela_to_svara() {
+        assert_eq!(
+            mela_to_svara(1, true, true).unwrap(),
+            vec![
+                "S", "R₁", "G₁", "G₂", "G₃", "M₁", "M₂", "P", "D₁", "N₁", "N₂", "N₃"
+            ]
+        );
+        assert_eq!(
+            mela_to_svara(72, false, false).unwrap(),
+            vec![
+                "Sa", "Ri1", "Ri2", "Ri3", "Ga3", "Ma1", "Ma2", "Pa", "Dha1", "Dha2", "Dha3", "Ni3"
+            ]
+        );
+        assert_eq!(
+            mela_to_svara("chalanatta", false, false).unwrap(),
+            mela_to_svara(36, false, false).unwrap()
+        );
+        assert_eq!(
+            mela_to_svara("Kanakangi", true, true).unwrap(),
+            mela_to_svara(1, true, true).unwrap()
+        );
+        assert!(matches!(
+            mela_to_svara(0, true, true),
+            Err(ParameterError::InvalidMela(0))
+        ));
+        assert!(mela_to_svara(73, true, true).is_err());
+        assert!(matches!(
+            mela_to_svara("bilaval", true, true),
+            Err(ParameterError::UnknownMela(_))
+        ));
+    }
+
+    #[test]
+    fn test_mela_to_degrees() {
+        assert_eq!(mela_to_degrees(1).unwrap(), [0, 1, 2, 5, 7, 8, 9]);
+        assert_eq!(mela_to_degrees("kanakangi").unwrap(), [0, 1, 2, 5, 7, 8, 9]);
+        assert_eq!(
+            mela_to_degrees("dheerasankarabharanam").unwrap(),
+            [0, 2, 4, 5, 7, 9, 11]
+        );
+        assert_eq!(mela_to_degrees(65).unwrap(), [0, 2, 4, 6, 7, 9, 11]);
+        assert_eq!(mela_to_degrees(72).unwrap(), [0, 3, 4, 6, 7, 10, 11]);
+        assert!(mela_to_degrees("nope").is_err());
+        // the svara spelling and the degrees agree for every mela
+        for (name, number) in list_mela() {
+            let svara = mela_to_svara(name, true, false).unwrap();
+            let degrees = mela_to_degrees(number).unwrap();
+            assert!(svara[degrees[1] as usize].starts_with('R'), "{}", name);
+            assert!(svara[degrees[2] as usize].starts_with('G'), "{}", name);
+            assert!(svara[degrees[3] as usize].starts_with('M'), "{}", name);
+            assert!(svara[degrees[5] as usize].starts_with('D'), "{}", name);
+            assert!(svara[degrees[6] as usize].starts_with('N'), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_mela_to_notes() {
+        let sa: Note = "D4".parse().unwrap();
+        let notes = mela_to_notes("mayamalavagaula", &sa).unwrap();
+        let pitches: Vec<_> = notes.iter().map(|note| note.pitch()).collect();
+        assert_eq!(pitches, vec![62, 63, 66, 67, 69, 70, 73]);
+        assert!(mela_to_notes(0, &sa).is_err());
+    }
+
+    #[test]
+    fn test_thaat() {
+        assert_eq!(thaat_to_degrees("bilaval").unwrap(), [0, 2, 4, 5, 7, 9, 11]);
+        assert_eq!(thaat_to_degrees("Todi").unwrap(), [0, 1, 3, 6, 7, 8, 11]);
+        assert!(matches!(
+            thaat_to_degrees("kanakangi"),
+            Err(ParameterError::UnknownThaat(_))
+        ));
+        let thaats = list_thaat();
+        assert_eq!(thaats.len(), 10);
+        assert!(thaats.iter().all(|thaat| thaat_to_degrees(thaat).is_ok()));
+    }
+
+    #[test]
+    fn test_list_mela() {
+        let melas = list_mela();
+        assert_eq!(melas.len(), 72);
+        assert_eq!(melas[0], ("kanakangi", 1));
+        assert_eq!(melas[71], ("rasikapriya", 72));
+        assert!(melas
+            .iter()
+            .all(|&(name, number)| Mela::from(name).number().unwrap() == number));
+    }
+
+    #[test]
+    fn test_midi_to_svara_c() {
+        let svara: Vec<_> = [60., 61., 62.]
+            .iter()
+            .map(|&midi| midi_to_svara_c(midi, 60., 1, true, true, true).unwrap())
+            .collect();
+        assert_eq!(svara, vec!["S", "R₁", "G₁"]);
+        let svara: Vec<_> = [60., 61., 62.]
+            .iter()
+            .map(|&midi| midi_to_svara_c(midi, 60., 36, false, true, false).unwrap())
+            .collect();
+        assert_eq!(svara, vec!["Sa", "Ri1", "Ri2"]);
+        assert_eq!(
+            midi_to_svara_c(73., 60., 1, true, true, true).unwrap(),
+            "R\u{307}₁"
+        );
+        assert_eq!(
+            midi_to_svara_c(59., 60., 1, true, true, false).unwrap(),
+            "N3,"
+        );
+        assert!(midi_to_svara_c(60., 60., 0, true, true, true).is_err());
+        assert_eq!(
+            hz_to_svara_c(130.5, 261., 1, true, true, true).unwrap(),
+            "S\u{323}"
+        );
+    }
+
+    #[test]
+    fn test_key_to_notes() {
+        assert_eq!(
+            key_to_notes("C:maj", true, false).unwrap(),
+            vec!["C", "C♯", "D", "D♯", "E", "F", "F♯", "G", "G♯", "A", "A♯", "B"]
+        );
+        assert_eq!(
+            key_to_notes("Eb:maj", true, false).unwrap(),
+            vec!["C", "D♭", "D", "E♭", "E", "F", "G♭", "G", "A♭", "A", "B♭", "B"]
+        );
+        assert_eq!(
+            key_to_notes("F#:maj", false, false).unwrap(),
+            vec!["C", "C#", "D", "D#", "E", "E#", "F#", "G", "G#", "A", "A#", "B"]
+        );
+        assert_eq!(
+            key_to_notes("G#:min", true, true).unwrap(),
+            vec![
+                "C♮", "C♯", "D♮", "D♯", "E", "F♮", "F♯", "G♮", "G♯", "A♮", "A♯", "B"
+            ]
+        );
+        // keys far around the circle of fifths need double accidentals
+        assert_eq!(
+            key_to_notes("G#:maj", true, false).unwrap(),
+            vec![
+                "B♯", "C♯", "D", "D♯", "E", "E♯", "F♯", "F𝄪", "G♯", "A", "A♯", "B"
+            ]
+        );
+        assert_eq!(
+            key_to_notes("Fb:maj", false, false).unwrap(),
+            vec!["C", "Db", "D", "Eb", "Fb", "F", "Gb", "G", "Ab", "Bbb", "Bb", "Cb"]
+        );
+        assert_eq!(
+            key_to_notes("B#:maj", true, false).unwrap(),
+            vec![
+                "B♯", "C♯", "C𝄪", "D♯", "D𝄪", "E♯", "F♯", "F𝄪", "G♯", "G𝄪", "A♯", "A𝄪"
+            ]
+        );
+        assert!(matches!(
+            key_to_notes("C:lydian", true, false),
+            Err(ParameterError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_to_degrees() {
+        assert_eq!(key_to_degrees("C:maj").unwrap(), [0, 2, 4, 5, 7, 9, 11]);
+        assert_eq!(key_to_degrees("C#:maj").unwrap(), [1, 3, 5, 6, 8, 10, 0]);
+        assert_eq!(key_to_degrees("A:min").unwrap(), [9, 11, 0, 2, 4, 5, 7]);
+        assert!(key_to_degrees("A").is_err());
+    }
+
+    #[test]
+    fn test_midi_to_note_in_key() {
+        let key = |key: &str| key.parse::<Key>().unwrap();
+        assert_eq!(
+            midi_to_note(70., true, false, &key("Bb:maj"), true).unwrap(),
+            "B♭4"
+        );
+        assert_eq!(
+            midi_to_note(70., true, false, &key("Bb:maj"), false).unwrap(),
+            "Bb4"
+        );
+        assert_eq!(
+            midi_to_note(70., true, false, &key("B:maj"), true).unwrap(),
+            "A♯4"
+        );
+        assert_eq!(
+            midi_to_note(67., true, false, &key("G#:maj"), true).unwrap(),
+            "F𝄪4"
+        );
+        assert_eq!(
+            midi_to_note(67., true, false, &key("G#:maj"), false).unwrap(),
+            "F##4"
+        );
+        // spelled notes keep their own octave across the B/C boundary
+        assert_eq!(
+            midi_to_note(60., true, false, &key("C#:maj"), true).unwrap(),
+            "B♯3"
+        );
+        assert_eq!(
+            midi_to_note(59., true, false, &key("Cb:maj"), true).unwrap(),
+            "C♭4"
+        );
+        assert_eq!(
+            midi_to_note(58.8, true, true, &key("Cb:maj"), true).unwrap(),
+            "C♭4-20"
+        );
+        assert_eq!(
+            hz_to_note(466.16, 440., true, false, &key("Eb:maj"), true).unwrap(),
+            "B♭4"
+        );
+        for &k in &["C#:maj", "Cb:maj", "B#:maj", "Fb:maj", "Eb:min"] {
+            for midi in 0..128 {
+                let note = midi_to_note(midi as f32, true, false, &key(k), true).unwrap();
+                assert_eq!(note_to_midi(&note).unwrap(), midi as f32, "{}", note);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fifths_to_note() {
+        let note = |note: &str| note.parse::<Note>().unwrap();
+        assert_eq!(fifths_to_note(&note("C"), 6, true), "F♯");
+        assert_eq!(fifths_to_note(&note("G"), -3, true), "B♭");
+        assert_eq!(fifths_to_note(&note("Eb"), 14, true), "E♯");
+        assert_eq!(fifths_to_note(&note("Eb"), 21, true), "E𝄪");
+        assert_eq!(fifths_to_note(&note("F"), -15, false), "Bbbb");
+        assert_eq!(fifths_to_note(&note("F"), -15, true), "B𝄫♭");
+    }
+
+    #[test]
+    fn test_interval_to_fjs() {
+        let note = |note: &str| note.parse::<Note>().unwrap();
+        let fjs = |interval: f32, unison: &str, unicode| {
+            interval_to_fjs(interval, &note(unison), 65. / 63., unicode).unwrap()
+        };
+        assert_eq!(fjs(1., "C", true), "C");
+        assert_eq!(fjs(3. / 2., "C", true), "G");
+        assert_eq!(fjs(4. / 3., "F", true), "B♭");
+        assert_eq!(fjs(5. / 4., "A", true), "C♯⁵");
+        assert_eq!(fjs(6. / 5., "A", true), "C₅");
+        assert_eq!(fjs(7. / 4., "C", true), "B♭⁷");
+        assert_eq!(fjs(25. / 14., "F#", true), "E²⁵₇");
+        assert_eq!(fjs(25. / 14., "F#", false), "E^25_7");
+        assert_eq!(fjs(5. / 3., "C", true), "A⁵");
+        assert_eq!(fjs(11. / 8., "C", false), "F^11");
+        assert!(matches!(
+            interval_to_fjs(0., &note("C"), 65. / 63., true),
+            Err(ParameterError::InvalidInterval(_))
+        ));
+        assert!(interval_to_fjs(-1.5, &note("C"), 65. / 63., true).is_err());
+        assert!(interval_to_fjs(f32::INFINITY, &note("C"), 65. / 63., true).is_err());
+        for interval in &[1e30, 2_f32.powf(1. / 12.), std::f32::consts::PI] {
+            assert!(matches!(
+                interval_to_fjs(*interval, &note("C"), 65. / 63., true),
+                Err(ParameterError::UnknownInterval(_))
+            ));
+        }
+        assert_eq!(fjs(23. / 16., "C", false), "F#^23");
+    }
+
+    #[test]
+    fn test_hz_to_fjs() {
+        assert_eq!(
+            hz_to_fjs(&[55., 66., 88.], None, None, true).unwrap(),
+            vec!["A", "C₅", "F₅"]
+        );
+        let g: Note = "G".parse().unwrap();
+        assert_eq!(
+            hz_to_fjs(&[55., 66., 88.], Some(55.), Some(&g), true).unwrap(),
+            vec!["G", "B♭₅", "E♭₅"]
+        );
+        assert_eq!(
+            hz_to_fjs(&[220., 275.], Some(110.), None, false).unwrap(),
+            vec!["A", "C#^5"]
+        );
+        assert!(matches!(
+            hz_to_fjs(&[], None, None, true),
+            Err(ParameterError::NoFrequencies)
+        ));
+        assert!(hz_to_fjs(&[], Some(55.), None, true).unwrap().is_empty());
+        assert!(matches!(
+            hz_to_fjs(&[440., 466.1638], None, None, true),
+            Err(ParameterError::UnknownInterval(_))
+        ));
+        for frequencies in &[&[0., 55.][..], &[55., -1.], &[f32::NAN], &[f32::INFINITY]] {
+            assert!(matches!(
+                hz_to_fjs(frequencies, None, None, true),
+                Err(ParameterError::InvalidFrequency(_))
+            ));
+        }
+        assert!(matches!(
+            hz_to_fjs(&[55.], Some(0.), Some(&g), true),
+            Err(ParameterError::InvalidFrequency(_))
+        ));
+    }
+
+    #[test]
+    fn test_pythagorean_intervals() {
+        assert_all_close(
+            &pythagorean_intervals(7, true, false),
+            &[1., 1.125, 1.265625, 1.423828, 1.5, 1.6875, 1.898438],
+            1e-5,
+        );
+        assert_all_close(
+            &pythagorean_intervals(3, false, false),
+            &[1., 1.5, 1.125],
+            1e-6,
+        );
+        let intervals = pythagorean_intervals(12, true, true);
+        assert_eq!(intervals.len(), 12);
+        assert_eq!(intervals[0], 0.);
+        assert!((intervals[7] - 1.5_f32.log2()).abs() < 1e-6);
+        assert!(intervals.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_plimit_intervals() {
+        assert_all_close(
+            &plimit_intervals(&[3, 5], 12, true, false),
+            &[
+                1.,
+                16. / 15.,
+                9. / 8.,
+                6. / 5.,
+                5. / 4.,
+                4. / 3.,
+                45. / 32.,
+                3. / 2.,
+                8. / 5.,
+                5. / 3.,
+                9. / 5.,
+                15. / 8.,
+            ],
+            1e-5,
+        );
+        // 3-limit intervals grow symmetrically around the unison
+        assert_all_close(
+            &plimit_intervals(&[3], 5, true, false),
+            &[1., 9. / 8., 4. / 3., 3. / 2., 16. / 9.],
+            1e-5,
+        );
+        let intervals = plimit_intervals(&[3, 5, 7], 12, false, true);
+        assert_eq!(intervals.len(), 12);
+        assert_eq!(intervals[0], 0.);
+        assert!(intervals.iter().all(|&r| (0. ..1.).contains(&r)));
+    }
+
+    #[test]
+    fn test_interval_frequencies() {
+        let ji5 = interval_frequencies(24, 55., 440., Intervals::Ji5, 12, 0., true);
+        assert_all_close(
+            &ji5,
+            &[
+                55., 58.667, 61.875, 66., 68.75, 73.333, 77.344, 82.5, 88., 91.667, 99., 103.125,
+                110., 117.333, 123.75, 132., 137.5, 146.667, 154.688, 165., 176., 183.333, 198.,
+                206.25,
+            ],
+            1e-3,
+        );
+        let a1: Note = "A1".parse().unwrap();
+        assert_eq!(
+            interval_frequencies(24, &a1, 440., Intervals::Ji5, 12, 0., true),
+            ji5
+        );
+        assert_all_close(
+            &interval_frequencies(24, &a1, 440., Intervals::Equal, 12, 0., true),
+            &cqt_frequencies(24, &a1, 440., 12, 0.),
+            1e-3,
+        );
+        assert_all_close(
+            &interval_frequencies(3, 100., 440., Intervals::Equal, 12, 0.5, true),
+            &cqt_frequencies(3, 100., 440., 12, 0.5),
+            1e-3,
+        );
+        assert_all_close(
+            &interval_frequencies(5, 100., 440., Intervals::Ratios(&[1., 1.5]), 12, 0., true),
+            &[100., 150., 200., 300., 400.],
+            1e-3,
+        );
+        assert_all_close(
+            &interval_frequencies(3, 100., 440., Intervals::Pythagorean, 12, 0., false),
+            &[100., 150., 112.5],
+            1e-3,
+        );
+        assert!(
+            interval_frequencies(3, 100., 440., Intervals::Ratios(&[]), 12, 0., true).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for midi in 0..128 {
+            let note = midi_to_note(midi as f32, true, false, &Key::default(), true).unwrap();
+            assert_eq!(note_to_midi(&note).unwrap(), midi as f32, "{}", note);
+        }
+    }
+}
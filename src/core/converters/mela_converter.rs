@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+pub(crate) static MELAKARTA_MAP: Lazy<HashMap<&'static str, u8>> = Lazy::new(|| {
+    let names = [
+        "kanakangi",
+        "ratnangi",
+        "ganamurthi",
+        "vanaspathi",
+        "manavathi",
+        "tanarupi",
+        "senavathi",
+        "hanumathodi",
+        "dhenuka",
+        "natakapriya",
+        "kokilapriya",
+        "rupavathi",
+        "gayakapriya",
+        "vakulabharanam",
+        "mayamalavagaula",
+        "chakravakom",
+        "suryakantham",
+        "hatakambari",
+        "jhankaradhwani",
+        "natabhairavi",
+        "keeravani",
+        "kharaharapriya",
+        "gaurimanohari",
+        "varunapriya",
+        "mararanjani",
+        "charukesi",
+        "sarasangi",
+        "harikambhoji",
+        "dheerasankarabharanam",
+        "naganandini",
+        "yagapriya",
+        "ragavardhini",
+        "gangeyabhushani",
+        "vagadheeswari",
+        "sulini",
+        "chalanatta",
+        "salagam",
+        "jalarnavam",
+        "jhalavarali",
+        "navaneetham",
+        "pavani",
+        "raghupriya",
+        "gavambhodi",
+        "bhavapriya",
+        "subhapanthuvarali",
+        "shadvidhamargini",
+        "suvarnangi",
+        "divyamani",
+        "dhavalambari",
+        "namanarayani",
+        "kamavardhini",
+        "ramapriya",
+        "gamanasrama",
+        "viswambhari",
+        "syamalangi",
+        "shanmukhapriya",
+        "simhendramadhyamam",
+        "hemavathi",
+        "dharmavathi",
+        "neethimathi",
+        "kanthamani",
+        "rishabhapriya",
+        "latangi",
+        "vachaspathi",
+        "mechakalyani",
+        "chitrambari",
+        "sucharitra",
+        "jyotisvarupini",
+        "dhatuvardhini",
+        "nasikabhushani",
+        "kosalam",
+        "rasikapriya",
+    ];
+    let mut map = HashMap::with_capacity(names.len());
+    for (number, name) in (1..).zip(names.iter()) {
+        map.insert(*name, number);
+    }
+    map
+});
+
+pub(crate) static THAAT_MAP: Lazy<HashMap<&'static str, [i8; 7]>> = Lazy::new(|| {
+    let mut map = HashMap::with_capacity(10);
+    map.insert("bilaval", [0, 2, 4, 5, 7, 9, 11]);
+    map.insert("khamaj", [0, 2, 4, 5, 7, 9, 10]);
+    map.insert("kafi", [0, 2, 3, 5, 7, 9, 10]);
+    map.insert("asavari", [0, 2, 3, 5, 7, 8, 10]);
+    map.insert("bhairavi", [0, 1, 3, 5, 7, 8, 10]);
+    map.insert("kalyan", [0, 2, 4, 6, 7, 9, 11]);
+    map.insert("marva", [0, 1, 4, 6, 7, 9, 11]);
+    map.insert("poorvi", [0, 1, 4, 6, 7, 8, 11]);
+    map.insert("todi", [0, 1, 3, 6, 7, 8, 11]);
+    map.insert("bhairav", [0, 1, 4, 5, 7, 8, 11]);
+    map
+});
+
+#[cfg(test)]
+mod tests {
+    use super::{MELAKARTA_MAP, THAAT_MAP};
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(MELAKARTA_MAP.len(), 72);
+        assert_eq!(MELAKARTA_MAP["kanakangi"], 1);
+        assert_eq!(MELAKARTA_MAP["dheerasankarabharanam"], 29);
+        assert_eq!(MELAKARTA_MAP["mechakalyani"], 65);
+        assert_eq!(MELAKARTA_MAP["rasikapriya"], 72);
+        let mut numbers: Vec<_> = MELAKARTA_MAP.values().cloned().collect();
+        numbers.sort_unstable();
+        assert_eq!(numbers, (1..=72).collect::<Vec<_>>());
+        assert_eq!(THAAT_MAP.len(), 10);
+        assert_eq!(THAAT_MAP["bilaval"], [0, 2, 4, 5, 7, 9, 11]);
+    }
+}
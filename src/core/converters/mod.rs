@@ -1 +1,2 @@
+pub mod mela_converter;
 pub mod note_converter;
@@ -0,0 +1,292 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+// Semitones of the major and perfect simple intervals, unison to seventh.
+const NUMBER_TO_SEMITONES: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IntervalError {
+    #[error("Improper interval format: {0}")]
+    InvalidFormat(String),
+    #[error("{quality} is not a quality of {number}")]
+    InvalidQuality { quality: Quality, number: u8 },
+    #[error("interval of {0} staff steps is out of range")]
+    NumberOutOfRange(i32),
+}
+
+/// Quality of an interval, augmented and diminished possibly more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quality {
+    Perfect,
+    Major,
+    Minor,
+    Augmented(u8),
+    Diminished(u8),
+}
+
+impl std::fmt::Display for Quality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Quality::Perfect => f.write_str("P"),
+            Quality::Major => f.write_str("M"),
+            Quality::Minor => f.write_str("m"),
+            Quality::Augmented(times) => f.write_str(&"A".repeat(times as usize)),
+            Quality::Diminished(times) => f.write_str(&"d".repeat(times as usize)),
+        }
+    }
+}
+
+/// An ascending interval such as a minor third (`m3`) or an augmented fourth (`A4`),
+/// compound intervals (`M9`, `P15`) included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    quality: Quality,
+    number: u8,
+}
+
+impl Interval {
+    /// Fails for a quality the number cannot have, such as a major fifth,
+    /// for a number of 0 and for intervals narrower than a unison.
+    pub fn new(quality: Quality, number: u8) -> Result<Self, IntervalError> {
+        let interval = Interval { quality, number };
+        let valid = match quality {
+            _ if number == 0 => false,
+            Quality::Perfect => interval.is_perfect_number(),
+            Quality::Major | Quality::Minor => !interval.is_perfect_number(),
+            Quality::Augmented(times) | Quality::Diminished(times) => times > 0,
+        };
+        if valid && (0..=i8::MAX as i32).contains(&interval.width()) {
+            Ok(interval)
+        } else {
+            Err(IntervalError::InvalidQuality { quality, number })
+        }
+    }
+
+    /// The interval of the given staff `number` spanning `semitones`,
+    /// checked by [`Interval::new`].
+    pub(crate) fn from_semitones(number: i32, semitones: i32) -> Result<Self, IntervalError> {
+        let number = u8::try_from(number)
+            .ok()
+            .filter(|&number| number > 0)
+            .ok_or(IntervalError::NumberOutOfRange(number))?;
+        let perfect = Interval {
+            quality: Quality::Perfect,
+            number,
+        };
+        // more than 255 times augmented or diminished is too wide for `new` anyway
+        let times = |d: i32| u8::try_from(d).unwrap_or(u8::MAX);
+        let quality = match (
+            perfect.is_perfect_number(),
+            semitones - perfect.base_semitones(),
+        ) {
+            (true, 0) => Quality::Perfect,
+            (false, 0) => Quality::Major,
+            (false, -1) => Quality::Minor,
+            (_, d) if d > 0 => Quality::Augmented(times(d)),
+            (true, d) => Quality::Diminished(times(-d)),
+            (false, d) => Quality::Diminished(times(-d - 1)),
+        };
+        Interval::new(quality, number)
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Staff steps counted inclusively, 1 for a unison, 8 for an octave.
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    pub fn is_compound(&self) -> bool {
+        self.number > 8
+    }
+
+    // unisons, fourths, fifths and their compounds
+    fn is_perfect_number(&self) -> bool {
+        matches!((self.number - 1) % 7, 0 | 3 | 4)
+    }
+
+    // semitones of the major or perfect interval of this number
+    fn base_semitones(&self) -> i32 {
+        let steps = self.number as usize - 1;
+        NUMBER_TO_SEMITONES[steps % 7] as i32 + 12 * (steps / 7) as i32
+    }
+
+    // width without the i8 range checked by `new`
+    fn width(&self) -> i32 {
+        let base = self.base_semitones();
+        match self.quality {
+            Quality::Perfect | Quality::Major => base,
+            Quality::Minor => base - 1,
+            Quality::Augmented(times) => base + times as i32,
+            Quality::Diminished(times) if self.is_perfect_number() => base - times as i32,
+            Quality::Diminished(times) => base - 1 - times as i32,
+        }
+    }
+
+    /// Width in semitones, 3 for a minor third.
+    pub fn semitones(&self) -> i8 {
+        self.width() as i8
+    }
+}
+
+impl FromStr for Interval {
+    type Err = IntervalError;
+    fn from_str(input: &str) -> Result<Interval, IntervalError> {
+        let invalid = || IntervalError::InvalidFormat(input.to_string());
+        let split = input
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (quality, number) = input.split_at(split);
+        let times = u8::try_from(quality.len()).map_err(|_| invalid())?;
+        let quality = match quality {
+            "P" => Quality::Perfect,
+            "M" => Quality::Major,
+            "m" => Quality::Minor,
+            q if !q.is_empty() && q.chars().all(|c| c == 'A') => Quality::Augmented(times),
+            q if !q.is_empty() && q.chars().all(|c| c == 'd') => Quality::Diminished(times),
+            _ => return Err(invalid()),
+        };
+        if !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let number = number.parse().map_err(|_| invalid())?;
+        Interval::new(quality, number)
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.quality, self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interval, IntervalError, Quality};
+    use crate::core::Note;
+
+    #[test]
+    fn test_parse() {
+        for (name, quality, number, semitones) in &[
+            ("P1", Quality::Perfect, 1, 0),
+            ("m3", Quality::Minor, 3, 3),
+            ("M3", Quality::Major, 3, 4),
+            ("A4", Quality::Augmented(1), 4, 6),
+            ("d5", Quality::Diminished(1), 5, 6),
+            ("P5", Quality::Perfect, 5, 7),
+            ("d7", Quality::Diminished(1), 7, 9),
+            ("P8", Quality::Perfect, 8, 12),
+            ("M9", Quality::Major, 9, 14),
+            ("AA4", Quality::Augmented(2), 4, 7),
+            ("dd3", Quality::Diminished(2), 3, 1),
+        ] {
+            let interval: Interval = name.parse().unwrap();
+            assert_eq!(interval.quality(), *quality, "{}", name);
+            assert_eq!(interval.number(), *number, "{}", name);
+            assert_eq!(interval.semitones(), *semitones, "{}", name);
+            assert_eq!(&interval.to_string(), name);
+        }
+        assert!(Interval::new(Quality::Major, 9).unwrap().is_compound());
+        for name in &["", "3", "M", "x3", "Ad4", "m-3", "P300", "M+3"] {
+            assert!(matches!(
+                name.parse::<Interval>(),
+                Err(IntervalError::InvalidFormat(_))
+            ));
+        }
+        for name in &[
+            format!("{}4", "A".repeat(257)),
+            format!("{}5", "d".repeat(256)),
+        ] {
+            assert!(matches!(
+                name.parse::<Interval>(),
+                Err(IntervalError::InvalidFormat(_))
+            ));
+        }
+        for name in &["M5", "P3", "m8", "d1", "dd2", "M0", "P99"] {
+            assert!(matches!(
+                name.parse::<Interval>(),
+                Err(IntervalError::InvalidQuality { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_transpose() {
+        let c: Note = "C4".parse().unwrap();
+        for (interval, name, accidental, pitch) in &[
+            ("m3", "E", -1, 63),
+            ("A2", "D", 1, 63),
+            ("P5", "G", 0, 67),
+            ("A4", "F", 1, 66),
+            ("d5", "G", -1, 66),
+            ("M7", "B", 0, 71),
+            ("P8", "C", 0, 72),
+            ("m10", "E", -1, 75),
+        ] {
+            let note = c.transpose(interval.parse().unwrap()).unwrap();
+            assert_eq!(note.pitchname(), *name, "{}", interval);
+            assert_eq!(note.accidental() as i8, *accidental, "{}", interval);
+            assert_eq!(note.pitch(), *pitch, "{}", interval);
+        }
+        let b: Note = "B3".parse().unwrap();
+        let note = b.transpose("M3".parse().unwrap()).unwrap();
+        assert_eq!(
+            (note.pitchname(), note.accidental(), note.pitch()),
+            ("D", 1., 63)
+        );
+        let note: Note = "A𝄲4+20".parse().unwrap();
+        let note = note.transpose("P4".parse().unwrap()).unwrap();
+        assert_eq!(note.pitchname(), "D");
+        assert_eq!(note.quarter_tones(), 1);
+        assert!((note.pitch_f32() - 74.7).abs() < 1e-4);
+        let high: Note = "C127".parse().unwrap();
+        assert!(high.transpose("P8".parse().unwrap()).is_none());
+        assert_eq!(
+            high.transpose("M7".parse().unwrap()).unwrap().to_string(),
+            "B127"
+        );
+        let note: Note = "C𝄪𝄪4".parse().unwrap();
+        assert!(note.transpose("A1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_interval_to() {
+        let c: Note = "C4".parse().unwrap();
+        for (other, interval) in &[
+            ("C4", "P1"),
+            ("Eb4", "m3"),
+            ("D#4", "A2"),
+            ("F#4", "A4"),
+            ("Gb4", "d5"),
+            ("C5", "P8"),
+            ("D5", "M9"),
+            ("Bbb4", "d7"),
+            ("C#4", "A1"),
+            ("Cb4", "A1"),
+            ("B3", "m2"),
+        ] {
+            let other: Note = other.parse().unwrap();
+            let to = c.interval_to(&other).unwrap();
+            assert_eq!(&to.to_string(), interval, "{:?}", other);
+            assert_eq!(to, other.interval_to(&c).unwrap());
+        }
+        let e: Note = "E4".parse().unwrap();
+        let interval = e.interval_to(&"C5".parse().unwrap()).unwrap();
+        assert_eq!(interval.to_string(), "m6");
+        assert_eq!(e.transpose(interval).unwrap().pitch(), 72);
+        let note: Note = "C##4".parse().unwrap();
+        assert!(matches!(
+            note.interval_to(&"Dbb4".parse().unwrap()),
+            Err(IntervalError::InvalidQuality { .. })
+        ));
+        let note: Note = "C-128".parse().unwrap();
+        assert!(matches!(
+            note.interval_to(&"C127".parse().unwrap()),
+            Err(IntervalError::NumberOutOfRange(_))
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+use std::str::FromStr;
+
+use super::convert::ParameterError;
+use super::converters::note_converter;
+use super::note::Letter;
+
+const SCALE_MAJOR: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+const SCALE_MINOR: [i8; 7] = [0, 2, 3, 5, 7, 8, 10];
+
+const NOTES_SHARP: [(Letter, i8); 12] = [
+    (Letter::C, 0),
+    (Letter::C, 1),
+    (Letter::D, 0),
+    (Letter::D, 1),
+    (Letter::E, 0),
+    (Letter::F, 0),
+    (Letter::F, 1),
+    (Letter::G, 0),
+    (Letter::G, 1),
+    (Letter::A, 0),
+    (Letter::A, 1),
+    (Letter::B, 0),
+];
+
+const NOTES_FLAT: [(Letter, i8); 12] = [
+    (Letter::C, 0),
+    (Letter::D, -1),
+    (Letter::D, 0),
+    (Letter::E, -1),
+    (Letter::E, 0),
+    (Letter::F, 0),
+    (Letter::G, -1),
+    (Letter::G, 0),
+    (Letter::A, -1),
+    (Letter::A, 0),
+    (Letter::B, -1),
+    (Letter::B, 0),
+];
+
+// Respellings applied one by one once a key has 6 or more sharps.
+const SHARP_CORRECTIONS: [(usize, (Letter, i8)); 7] = [
+    (5, (Letter::E, 1)),
+    (0, (Letter::B, 1)),
+    (7, (Letter::F, 2)),
+    (2, (Letter::C, 2)),
+    (9, (Letter::G, 2)),
+    (4, (Letter::D, 2)),
+    (11, (Letter::A, 2)),
+];
+
+// Respellings applied one by one once a key has 6 or more flats.
+const FLAT_CORRECTIONS: [(usize, (Letter, i8)); 6] = [
+    (11, (Letter::C, -1)),
+    (4, (Letter::F, -1)),
+    (9, (Letter::B, -2)),
+    (2, (Letter::E, -2)),
+    (7, (Letter::A, -2)),
+    (0, (Letter::D, -2)),
+];
+
+/// A major or minor key, such as `C:maj` or `Eb:min`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    tonic: Letter,
+    accidental: i8,
+    major: bool,
+}
+
+impl Key {
+    /// Pitch class of the tonic, 0 being C.
+    pub fn tonic_pitch(&self) -> i8 {
+        (self.tonic.pitch() + self.accidental).rem_euclid(12)
+    }
+
+    pub fn is_major(&self) -> bool {
+        self.major
+    }
+
+    /// Pitch classes of the scale degrees of this key.
+    pub fn degrees(&self) -> [i8; 7] {
+        let scale = if self.major { SCALE_MAJOR } else { SCALE_MINOR };
+        let tonic = self.tonic_pitch();
+        let mut degrees = [0; 7];
+        for (degree, step) in degrees.iter_mut().zip(scale.iter()) {
+            *degree = (step + tonic).rem_euclid(12);
+        }
+        degrees
+    }
+
+    /// Letter name and accidental of each of the 12 pitch classes, as spelled in this key.
+    pub(crate) fn spelling(&self) -> [(Letter, i8); 12] {
+        let pitch = self.tonic.pitch() + self.accidental;
+        // position of the (relative major) tonic around the circle of fifths
+        let tonic_number = if self.major {
+            (pitch * 7).rem_euclid(12)
+        } else {
+            (pitch * 7 + 9).rem_euclid(12)
+        };
+
+        let use_sharps = match self.accidental {
+            a if a < 0 => false,
+            a if a > 0 => true,
+            _ => tonic_number < 6,
+        };
+
+        if use_sharps {
+            // tell B♯:maj apart from C:maj
+            let n_sharps = if tonic_number == 0 && self.tonic == Letter::B {
+                12
+            } else {
+                tonic_number as usize
+            };
+            let mut notes = NOTES_SHARP;
+            for &(index, name) in SHARP_CORRECTIONS
+                .iter()
+                .take((n_sharps + 1).saturating_sub(6))
+            {
+                notes[index] = name;
+            }
+            notes
+        } else {
+            let n_flats = (12 - tonic_number as usize) % 12;
+            let mut notes = NOTES_FLAT;
+            for &(index, name) in FLAT_CORRECTIONS
+                .iter()
+                .take((n_flats + 1).saturating_sub(6))
+            {
+                notes[index] = name;
+            }
+            notes
+        }
+    }
+}
+
+impl Default for Key {
+    fn default() -> Self {
+        Key {
+            tonic: Letter::C,
+            accidental: 0,
+            major: true,
+        }
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.tonic.as_str())?;
+        f.write_str(
+            note_converter::I8_TO_ACCIDENTAL
+                [&(self.accidental * note_converter::QUARTER_TONES_PER_SEMITONE)],
+        )?;
+        f.write_str(if self.major { ":maj" } else { ":min" })
+    }
+}
+
+impl FromStr for Key {
+    type Err = ParameterError;
+    fn from_str(input: &str) -> Result<Key, ParameterError> {
+        let invalid = || ParameterError::InvalidKey(input.to_string());
+        let (tonic, scale) = input.split_once(':').ok_or_else(invalid)?;
+
+        let mut chars = tonic.chars();
+        let tonic = *chars
+            .next()
+            .and_then(|c| note_converter::PITCH_NAMES.get(&c.to_ascii_uppercase()))
+            .ok_or_else(invalid)?;
+        let accidental = match chars.next() {
+            None => 0,
+            Some(c @ ('#' | '♯' | 'b' | '!' | '♭')) => {
+                note_converter::ACCIDENTAL_TO_I8[&c] / note_converter::QUARTER_TONES_PER_SEMITONE
+            }
+            Some(_) => return Err(invalid()),
+        };
+        if chars.next().is_some() {
+            return Err(invalid());
+        }
+
+        let major = match scale {
+            "maj" | "major" => true,
+            "min" | "minor" => false,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Key {
+            tonic,
+            accidental,
+            major,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Key;
+
+    #[test]
+    fn test_parse() {
+        let key: Key = "Eb:maj".parse().unwrap();
+        assert_eq!(key.tonic_pitch(), 3);
+        assert!(key.is_major());
+        assert_eq!(key.to_string(), "E♭:maj");
+        let key: Key = "f#:minor".parse().unwrap();
+        assert_eq!(key.tonic_pitch(), 6);
+        assert!(!key.is_major());
+        assert_eq!(key.to_string(), "F♯:min");
+        assert_eq!(Key::default(), "C:maj".parse().unwrap());
+        for key in &["", "C", "H:maj", "C:dor", "C##:maj", "Cb4:maj", ":maj"] {
+            assert!(key.parse::<Key>().is_err(), "{} should not parse", key);
+        }
+    }
+
+    #[test]
+    fn test_degrees() {
+        let key: Key = "C:maj".parse().unwrap();
+        assert_eq!(key.degrees(), [0, 2, 4, 5, 7, 9, 11]);
+        let key: Key = "A:min".parse().unwrap();
+        assert_eq!(key.degrees(), [9, 11, 0, 2, 4, 5, 7]);
+        let key: Key = "Cb:maj".parse().unwrap();
+        assert_eq!(key.degrees(), [11, 1, 3, 4, 6, 8, 10]);
+    }
+}
@@ -1,5 +1,16 @@
+pub mod chord;
 pub mod convert;
-mod converters;
+pub(crate) mod converters;
+pub mod interval;
+pub mod key;
 pub mod note;
+pub mod range;
+pub mod scale;
 
-pub use note::Note;
+pub use chord::Chord;
+pub use converters::note_converter::{NoteParseError, NoteParser, ParseOptions};
+pub use interval::Interval;
+pub use key::Key;
+pub use note::{Letter, Note, NoteError};
+pub use range::NoteRange;
+pub use scale::Scale;
@@ -1,57 +1,661 @@
-use super::converters::note_converter;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 
-#[derive(Debug, Clone)]
+use thiserror::Error;
+
+use super::convert;
+use super::converters::note_converter::{self, NoteParseError, NoteParser, ParseOptions};
+use super::interval::{Interval, IntervalError};
+use super::key::Key;
+
+const SEMITONE_TO_NAME: [(Letter, i8); 12] = [
+    (Letter::C, 0),
+    (Letter::C, 1),
+    (Letter::D, 0),
+    (Letter::D, 1),
+    (Letter::E, 0),
+    (Letter::F, 0),
+    (Letter::F, 1),
+    (Letter::G, 0),
+    (Letter::G, 1),
+    (Letter::A, 0),
+    (Letter::A, 1),
+    (Letter::B, 0),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NoteError {
+    #[error("invalid pitch name: {0}")]
+    InvalidPitchName(String),
+    #[error("accidental of {0} semitones is out of range")]
+    AccidentalOutOfRange(i8),
+    #[error("MIDI number {0} is out of range")]
+    MidiOutOfRange(i32),
+    #[error("cents must be finite and within -2147483.647..=2147483.647")]
+    CentsOutOfRange,
+    #[error("MIDI number is not finite")]
+    NonFiniteMidi,
+    #[error("frequency must be finite and strictly positive")]
+    InvalidFrequency,
+}
+
+/// Letter name of a note, ordered from C to B.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Letter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+impl Letter {
+    pub const ALL: [Letter; 7] = [
+        Letter::C,
+        Letter::D,
+        Letter::E,
+        Letter::F,
+        Letter::G,
+        Letter::A,
+        Letter::B,
+    ];
+
+    /// Semitones of the natural note above C.
+    pub const fn pitch(self) -> i8 {
+        match self {
+            Letter::C => 0,
+            Letter::D => 2,
+            Letter::E => 4,
+            Letter::F => 5,
+            Letter::G => 7,
+            Letter::A => 9,
+            Letter::B => 11,
+        }
+    }
+
+    /// Position from C, 0 to 6.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The letter at `index` from C, wrapping around after B.
+    pub(crate) const fn from_index(index: usize) -> Letter {
+        Letter::ALL[index % 7]
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Letter::C => "C",
+            Letter::D => "D",
+            Letter::E => "E",
+            Letter::F => "F",
+            Letter::G => "G",
+            Letter::A => "A",
+            Letter::B => "B",
+        }
+    }
+}
+
+impl std::fmt::Display for Letter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A spelled note, or a pitch class when it has no octave.
+///
+/// A pitch class sounds in octave 0, as a bare note name does in librosa.
+///
+/// Equality and hashing tell spellings apart, so B♯3 and C4 differ, while the
+/// ordering goes by pitch first, see [`Note::cmp_pitch`] and [`Note::cmp_spelling`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Note {
-    pitchname: &'static str,
-    pitch: i8,
+    letter: Letter,
     accidental: i8,
-    octave: i8,
-    cents: i8,
+    octave: Option<i8>,
+    millicents: i32,
 }
 
 impl Note {
-    pub fn new(pitchname: &'static str, accidental: i8, octave: i8, cents: i8) -> Self {
-        let pitch: i8 = *note_converter::NAME_TO_PITCH.get(pitchname).unwrap();
-        Note {
-            pitchname,
-            pitch,
+    /// A note in the written `octave`, octave 4 holding middle C.
+    ///
+    /// # Panics
+    ///
+    /// When `accidental` is beyond a quadruple sharp or flat, see [`Note::try_new`].
+    pub const fn new(letter: Letter, accidental: i8, octave: i8, cents: i8) -> Self {
+        let max = note_converter::MAX_QUARTER_TONES / note_converter::QUARTER_TONES_PER_SEMITONE;
+        assert!(
+            -max <= accidental && accidental <= max,
+            "accidental beyond a quadruple sharp or flat"
+        );
+        Note::from_quarter_tones(
+            letter,
+            accidental * note_converter::QUARTER_TONES_PER_SEMITONE,
             octave,
-            accidental,
             cents,
+        )
+    }
+
+    /// Like [`Note::new`], with the letter given by name, failing on an unknown name
+    /// or on an accidental beyond a quadruple sharp or flat.
+    pub fn try_new(
+        pitchname: &str,
+        accidental: i8,
+        octave: i8,
+        cents: i8,
+    ) -> Result<Self, NoteError> {
+        let mut chars = pitchname.chars();
+        let letter = match (chars.next(), chars.next()) {
+            (Some(c), None) => note_converter::PITCH_NAMES.get(&c.to_ascii_uppercase()),
+            _ => None,
+        }
+        .ok_or_else(|| NoteError::InvalidPitchName(pitchname.to_string()))?;
+        accidental
+            .checked_mul(note_converter::QUARTER_TONES_PER_SEMITONE)
+            .filter(|accidental| note_converter::I8_TO_ACCIDENTAL.contains_key(accidental))
+            .ok_or(NoteError::AccidentalOutOfRange(accidental))?;
+        Ok(Note::new(*letter, accidental, octave, cents))
+    }
+
+    /// Like [`Note::new`], with the accidental counted in quarter tones,
+    /// so `1` is a half-sharp and `-3` a sesqui-flat.
+    ///
+    /// # Panics
+    ///
+    /// When `accidental` is beyond a quadruple sharp or flat.
+    pub const fn from_quarter_tones(letter: Letter, accidental: i8, octave: i8, cents: i8) -> Self {
+        let max = note_converter::MAX_QUARTER_TONES;
+        assert!(
+            -max <= accidental && accidental <= max,
+            "accidental beyond a quadruple sharp or flat"
+        );
+        Note {
+            letter,
+            octave: Some(octave),
+            accidental,
+            millicents: cents as i32 * note_converter::MILLICENTS_PER_CENT,
+        }
+    }
+
+    /// Parse a note name with the default backend according to `options`,
+    /// [`str::parse`] uses [`ParseOptions::strict`].
+    pub fn parse_with(input: &str, options: &ParseOptions) -> Result<Note, NoteParseError> {
+        note_converter::DefaultParser::parse_with(input, options)
+    }
+
+    /// Parse a comma and/or whitespace separated list of notes, such as `"C, E, G"`
+    /// or `"C4 E4 G4"`, with one result per item.
+    ///
+    /// Error spans point into the whole of `input`, and an empty item between
+    /// two commas is reported as [`NoteParseError::Empty`].
+    ///
+    /// ```text
+    /// >>> librosa.note_to_midi(['C', 'E', 'G'])
+    /// array([12, 16, 19])
+    /// ```
+    pub fn parse_many(input: &str) -> Vec<Result<Note, NoteParseError>> {
+        if input.trim().is_empty() {
+            return Vec::new();
+        }
+        let mut notes = Vec::new();
+        let mut offset = 0;
+        for item in input.split(',') {
+            let mut words = item
+                .split_whitespace()
+                .map(|word| (word.as_ptr() as usize - input.as_ptr() as usize, word))
+                .peekable();
+            if words.peek().is_none() {
+                notes.push(
+                    Note::parse_with("", &ParseOptions::librosa())
+                        .map_err(|err| err.shifted(offset + item.len() - item.trim_start().len())),
+                );
+            }
+            for (start, word) in words {
+                notes.push(
+                    Note::parse_with(word, &ParseOptions::librosa())
+                        .map_err(|err| err.shifted(start)),
+                );
+            }
+            offset += item.len() + 1;
+        }
+        notes
+    }
+
+    /// Replace the cents of this note, keeping a thousandth of a cent of precision.
+    ///
+    /// A pitch class given cents is placed in octave 0, where it sounds,
+    /// since `C+10` reads as C in octave 10.
+    ///
+    /// # Panics
+    ///
+    /// When `cents` is not finite or beyond the range the parser accepts,
+    /// see [`Note::try_with_cents`].
+    pub fn with_cents(self, cents: f32) -> Self {
+        self.try_with_cents(cents)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`Note::with_cents`], failing when `cents` is not finite or beyond
+    /// `-2147483.647..=2147483.647`, so that the note always parses back.
+    pub fn try_with_cents(self, cents: f32) -> Result<Self, NoteError> {
+        let millicents = note_converter::cents_to_millicents(f64::from(cents))
+            .ok_or(NoteError::CentsOutOfRange)?;
+        Ok(self.with_millicents(millicents))
+    }
+
+    /// This note in the written `octave`, turning a pitch class into a concrete pitch.
+    pub fn with_octave(mut self, octave: i8) -> Self {
+        self.octave = Some(octave);
+        self
+    }
+
+    /// The pitch class of this note, dropping its octave and cents.
+    pub fn without_octave(mut self) -> Self {
+        self.octave = None;
+        self.millicents = 0;
+        self
+    }
+
+    // keep the pitch class of `self` a pitch class in derived notes
+    fn octave_like(self, other: &Note) -> Self {
+        if other.octave.is_some() {
+            self
+        } else {
+            self.without_octave()
+        }
+    }
+
+    pub(crate) fn with_millicents(mut self, millicents: i32) -> Self {
+        if millicents != 0 && self.octave.is_none() {
+            self.octave = Some(0);
         }
+        self.millicents = millicents;
+        self
+    }
+
+    pub fn cents(&self) -> f32 {
+        (self.millicents as f64 / note_converter::MILLICENTS_PER_CENT as f64) as f32
     }
 
+    /// MIDI number rounded to the nearest semitone, half to even, and clamped to `0..=255`,
+    /// see [`Note::midi_i32`] and [`Note::try_midi_u7`] for notes outside of it.
     pub fn pitch(&self) -> u8 {
-        let cents = (self.cents as f32 * 1e-2_f32).round() as u8;
-        (12 * self.octave + self.pitch + self.accidental) as u8 + cents
+        let midi = self.midi_i32();
+        if midi < 0 {
+            0
+        } else {
+            u8::try_from(midi).unwrap_or(u8::MAX)
+        }
+    }
+
+    /// MIDI number rounded to the nearest semitone, negative below C-1.
+    ///
+    /// Halves round to even, as in [`Note::from_midi`] and librosa,
+    /// so C4+50 is 60 and C♯4+50 is 62.
+    pub fn midi_i32(&self) -> i32 {
+        let semitone = 100 * i64::from(note_converter::MILLICENTS_PER_CENT);
+        let millicents = self.millicents_total();
+        let (midi, remainder) = (
+            millicents.div_euclid(semitone),
+            millicents.rem_euclid(semitone),
+        );
+        let midi = match (2 * remainder).cmp(&semitone) {
+            Ordering::Greater => midi + 1,
+            Ordering::Equal => midi + midi.rem_euclid(2),
+            Ordering::Less => midi,
+        };
+        i32::try_from(midi).expect("bounded by the i8 octave and the i32 millicents")
+    }
+
+    /// MIDI number rounded to the nearest semitone, failing outside of the
+    /// `0..=127` range of the MIDI protocol.
+    pub fn try_midi_u7(&self) -> Result<u8, NoteError> {
+        let midi = self.midi_i32();
+        u8::try_from(midi)
+            .ok()
+            .filter(|&midi| midi <= 127)
+            .ok_or(NoteError::MidiOutOfRange(midi))
     }
 
     pub fn pitch_f32(&self) -> f32 {
-        let cents = self.cents as f32 * 1e-2_f32;
-        (12 * self.octave + self.pitch + self.accidental) as f32 + cents
+        12. * f32::from(self.midi_octave())
+            + f32::from(self.letter.pitch())
+            + self.accidental()
+            + self.cents() * 1e-2_f32
+    }
+
+    /// Written octave, `None` for a pitch class.
+    pub fn octave(&self) -> Option<i8> {
+        self.octave
+    }
+
+    /// Whether this note has no octave.
+    pub fn is_pitch_class(&self) -> bool {
+        self.octave.is_none()
+    }
+
+    // octave counted from MIDI number 0, a pitch class sounding in octave 0
+    fn midi_octave(&self) -> i16 {
+        i16::from(self.octave.unwrap_or(0)) + 1
+    }
+
+    pub fn pitchname(&self) -> &'static str {
+        self.letter.as_str()
+    }
+
+    pub fn letter(&self) -> Letter {
+        self.letter
+    }
+
+    /// Accidental in (possibly fractional) semitones.
+    pub fn accidental(&self) -> f32 {
+        self.accidental as f32 / note_converter::QUARTER_TONES_PER_SEMITONE as f32
+    }
+
+    /// Accidental in quarter tones.
+    pub fn quarter_tones(&self) -> i8 {
+        self.accidental
+    }
+
+    /// Letter name and accidental in whole semitones.
+    pub(crate) fn spelling(&self) -> (Letter, i8) {
+        (
+            self.letter,
+            self.accidental / note_converter::QUARTER_TONES_PER_SEMITONE,
+        )
+    }
+
+    // pitch in thousandths of a cent, exact for quarter tones
+    fn millicents_total(&self) -> i64 {
+        let quarter_tones = i64::from(note_converter::QUARTER_TONES_PER_SEMITONE)
+            * (12 * i64::from(self.midi_octave()) + i64::from(self.letter.pitch()))
+            + i64::from(self.accidental);
+        quarter_tones * 50 * i64::from(note_converter::MILLICENTS_PER_CENT)
+            + i64::from(self.millicents)
+    }
+
+    /// Compare by sounding pitch only, so B♯3 and C4 are equal.
+    pub fn cmp_pitch(&self, other: &Note) -> Ordering {
+        self.millicents_total().cmp(&other.millicents_total())
+    }
+
+    /// Compare by written position on the staff, then by accidental and cents,
+    /// so B♯3 comes before C♭4, with pitch classes before any octave.
+    pub fn cmp_spelling(&self, other: &Note) -> Ordering {
+        (self.octave, self.letter, self.accidental, self.millicents).cmp(&(
+            other.octave,
+            other.letter,
+            other.accidental,
+            other.millicents,
+        ))
+    }
+
+    /// Whether both notes sound the same pitch, however they are spelled.
+    pub fn is_enharmonic(&self, other: &Note) -> bool {
+        self.cmp_pitch(other) == Ordering::Equal
+    }
+
+    /// The note `semitones` above this one, spelled as its scale `degree`, 1 being this note,
+    /// keeping any quarter tone and cents.
+    ///
+    /// `None` past octave 127 or when the accidental has no symbol.
+    pub(crate) fn above(&self, degree: u8, semitones: i8) -> Option<Note> {
+        let above = (self.letter.index() + usize::from(degree)).checked_sub(1)?;
+        let octaves = i8::try_from(above / 7).ok()?;
+        let octave = self.octave.unwrap_or(0).checked_add(octaves)?;
+        let letter = Letter::from_index(above);
+        let accidental = i32::from(self.letter.pitch()) + i32::from(semitones)
+            - i32::from(letter.pitch())
+            - 12 * i32::from(octaves);
+        let accidental = i8::try_from(
+            i32::from(self.accidental)
+                + accidental * i32::from(note_converter::QUARTER_TONES_PER_SEMITONE),
+        )
+        .ok()
+        .filter(|accidental| note_converter::I8_TO_ACCIDENTAL.contains_key(accidental))?;
+        Some(
+            Note::from_quarter_tones(letter, accidental, octave, 0)
+                .with_millicents(self.millicents)
+                .octave_like(self),
+        )
+    }
+
+    /// This note moved up by `interval`, keeping the letter names apart,
+    /// so that C transposed by a minor third is E♭ and not D♯.
+    ///
+    /// `None` past octave 127 or beyond a quadruple sharp.
+    pub fn transpose(&self, interval: Interval) -> Option<Note> {
+        self.above(interval.number(), interval.semitones())
+    }
+
+    /// The spelled interval between this note and `other`, in either order.
+    ///
+    /// Quarter tones and cents are ignored. Fails when the spellings make
+    /// no valid interval, such as C𝄪 up to D𝄫, or one wider than 127 semitones.
+    pub fn interval_to(&self, other: &Note) -> Result<Interval, IntervalError> {
+        let position = |note: &Note| {
+            let (letter, accidental) = note.spelling();
+            let step = 7 * i32::from(note.midi_octave()) + letter.index() as i32;
+            let pitch = 12 * i32::from(note.midi_octave())
+                + i32::from(letter.pitch())
+                + i32::from(accidental);
+            (step, pitch)
+        };
+        let (low, high) = {
+            let (a, b) = (position(self), position(other));
+            if b < a {
+                (b, a)
+            } else {
+                (a, b)
+            }
+        };
+        Interval::from_semitones(high.0 - low.0 + 1, high.1 - low.1)
+    }
+
+    /// Every spelling of this pitch with at most a double sharp or double flat,
+    /// from the lowest letter to the highest, this note and its cents included.
+    ///
+    /// ```text
+    /// B♯3, C4, D𝄫4
+    /// ```
+    pub fn enharmonics(&self) -> Vec<Note> {
+        let step = 7 * i32::from(self.midi_octave()) + self.letter.index() as i32;
+        let quarter_tones = i32::from(note_converter::QUARTER_TONES_PER_SEMITONE)
+            * (12 * i32::from(self.midi_octave()) + i32::from(self.letter.pitch()))
+            + i32::from(self.accidental);
+        (step - 3..=step + 3)
+            .filter_map(|step| {
+                let letter = Letter::from_index(step.rem_euclid(7) as usize);
+                let octave = step.div_euclid(7) - 1;
+                let accidental = quarter_tones
+                    - note_converter::QUARTER_TONES_PER_SEMITONE as i32
+                        * (12 * (octave + 1) + i32::from(letter.pitch()));
+                let accidental = i8::try_from(accidental)
+                    .ok()
+                    .filter(|accidental| accidental.abs() <= 4)?;
+                note_converter::I8_TO_ACCIDENTAL.get(&accidental)?;
+                Some(
+                    Note::from_quarter_tones(letter, accidental, i8::try_from(octave).ok()?, 0)
+                        .with_millicents(self.millicents)
+                        .octave_like(self),
+                )
+            })
+            .collect()
+    }
+
+    /// The same pitch spelled with the smallest sharp or no accidental, so D♭ becomes C♯.
+    pub fn respell_sharp(&self) -> Note {
+        self.enharmonics()
+            .into_iter()
+            .filter(|note| note.accidental >= 0)
+            .min_by_key(|note| note.accidental)
+            .unwrap_or_else(|| self.clone())
     }
 
-    pub fn octave(&self) -> u8 {
-        self.octave as u8
+    /// The same pitch spelled with the smallest flat or no accidental, so C♯ becomes D♭.
+    pub fn respell_flat(&self) -> Note {
+        self.enharmonics()
+            .into_iter()
+            .filter(|note| note.accidental <= 0)
+            .max_by_key(|note| note.accidental)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// The same pitch with the fewest accidentals, so B♯ becomes C and F𝄫 becomes E♭.
+    ///
+    /// Between two equally simple spellings, the one going the same way as this note wins.
+    pub fn simplest_spelling(&self) -> Note {
+        let sign = self.accidental.signum();
+        self.enharmonics()
+            .into_iter()
+            .min_by_key(|note| (note.accidental.abs(), note.accidental.signum() != sign))
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Frequency of this note in Hz, relative to the reference pitch `a4`.
+    pub fn hz(&self, a4: f32) -> f32 {
+        convert::midi_to_hz(self.pitch_f32(), a4)
+    }
+
+    /// Nearest note to a (fractional) MIDI number, spelled with sharps,
+    /// the remainder is kept as cents.
+    ///
+    /// # Panics
+    ///
+    /// When `midi` is not finite or beyond the octaves a note can hold,
+    /// see [`Note::try_from_midi`].
+    pub fn from_midi(midi: f32) -> Self {
+        Note::try_from_midi(midi).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`Note::from_midi`], failing when `midi` is not finite
+    /// or beyond the octaves a note can hold.
+    pub fn try_from_midi(midi: f32) -> Result<Self, NoteError> {
+        Note::spell_midi(midi, |pitch_class| SEMITONE_TO_NAME[pitch_class])
+    }
+
+    /// Nearest note to a (fractional) MIDI number, spelled as in `key`,
+    /// the remainder is kept as cents.
+    ///
+    /// # Panics
+    ///
+    /// When `midi` is not finite or beyond the octaves a note can hold,
+    /// see [`Note::try_from_midi_in_key`].
+    pub fn from_midi_in_key(midi: f32, key: &Key) -> Self {
+        Note::try_from_midi_in_key(midi, key).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`Note::from_midi_in_key`], failing when `midi` is not finite
+    /// or beyond the octaves a note can hold.
+    pub fn try_from_midi_in_key(midi: f32, key: &Key) -> Result<Self, NoteError> {
+        let spelling = key.spelling();
+        Note::spell_midi(midi, |pitch_class| spelling[pitch_class])
+    }
+
+    fn spell_midi<F: FnOnce(usize) -> (Letter, i8)>(
+        midi: f32,
+        spell: F,
+    ) -> Result<Self, NoteError> {
+        if !midi.is_finite() {
+            return Err(NoteError::NonFiniteMidi);
+        }
+        let pitch = midi.round_ties_even();
+        let cents = (midi - pitch) * 100.;
+        // saturates, so that anything too large is reported below
+        let pitch = pitch as i32;
+        let pitch_class = pitch.rem_euclid(12);
+        let (letter, accidental) = spell(usize::try_from(pitch_class).unwrap_or_default());
+        // octave of the spelled note, so that B♯3 is used for C4
+        let spelled = i32::from(letter.pitch()) + i32::from(accidental);
+        let octave = pitch
+            .checked_sub(spelled)
+            .and_then(|natural| i8::try_from(natural.div_euclid(12) - 1).ok())
+            .ok_or(NoteError::MidiOutOfRange(pitch))?;
+        Ok(Note::new(letter, accidental, octave, 0).with_cents(cents))
+    }
+
+    /// Nearest note to a frequency in Hz, relative to the reference pitch `a4`.
+    ///
+    /// # Panics
+    ///
+    /// When `hz` is not strictly positive and finite or the note is beyond
+    /// the octaves a note can hold, see [`Note::try_from_hz`].
+    pub fn from_hz(hz: f32, a4: f32) -> Self {
+        Note::try_from_hz(hz, a4).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`Note::from_hz`], failing when `hz` is not strictly positive and finite
+    /// or the note is beyond the octaves a note can hold.
+    pub fn try_from_hz(hz: f32, a4: f32) -> Result<Self, NoteError> {
+        if !hz.is_finite() || hz <= 0. {
+            return Err(NoteError::InvalidFrequency);
+        }
+        Note::try_from_midi(convert::hz_to_midi(hz, a4))
+    }
+
+    /// Render this note as a Hindustani svara relative to `sa`,
+    /// see [`convert::midi_to_svara_h`].
+    pub fn svara_h(&self, sa: &Note, abbr: bool, octave: bool, unicode: bool) -> String {
+        convert::midi_to_svara_h(self.pitch_f32(), sa.pitch_f32(), abbr, octave, unicode)
+    }
+
+    /// Render this note as a Carnatic svara of the melakarta raga `mela` relative to `sa`,
+    /// see [`convert::midi_to_svara_c`].
+    pub fn svara_c<'a, M: Into<convert::Mela<'a>>>(
+        &self,
+        sa: &Note,
+        mela: M,
+        abbr: bool,
+        octave: bool,
+        unicode: bool,
+    ) -> Result<String, convert::ParameterError> {
+        convert::midi_to_svara_c(
+            self.pitch_f32(),
+            sa.pitch_f32(),
+            mela,
+            abbr,
+            octave,
+            unicode,
+        )
+    }
+}
+
+impl PartialOrd for Note {
+    fn partial_cmp(&self, other: &Note) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// By pitch, then by spelling.
+impl Ord for Note {
+    fn cmp(&self, other: &Note) -> Ordering {
+        self.cmp_pitch(other).then_with(|| self.cmp_spelling(other))
     }
 }
 
 impl std::fmt::Display for Note {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.pitchname)?;
+        f.write_str(self.letter.as_str())?;
         f.write_fmt(format_args!(
             "{}",
             note_converter::I8_TO_ACCIDENTAL
                 .get(&self.accidental)
                 .unwrap(),
         ))?;
-        if self.octave > 1 {
-            f.write_fmt(format_args!("{}", self.octave))?;
+        if let Some(octave) = self.octave {
+            f.write_fmt(format_args!("{}", octave))?;
         }
-        if self.cents > 0 {
-            f.write_fmt(format_args!("+{}", self.cents))?
-        } else if self.cents < 0 {
-            f.write_fmt(format_args!("-{}", self.cents))?
+        if self.millicents != 0 {
+            let sign = if self.millicents > 0 { '+' } else { '-' };
+            let cents = self.millicents.unsigned_abs();
+            let millicents = note_converter::MILLICENTS_PER_CENT as u32;
+            f.write_fmt(format_args!("{}{}", sign, cents / millicents))?;
+            if !cents.is_multiple_of(millicents) {
+                let fraction = format!("{:03}", cents % millicents);
+                f.write_fmt(format_args!(".{}", fraction.trim_end_matches('0')))?;
+            }
         }
         Ok(())
     }
@@ -84,37 +688,420 @@ mod serde_ {
 
     #[cfg(test)]
     mod tests {
-        use super::super::Note;
+        use super::super::{Letter, Note};
 
         #[test]
         fn test_serialize() {
             assert_eq!(
-                serde_json::to_string(&Note::new("C", 1, 3, 0)).unwrap(),
+                serde_json::to_string(&Note::new(Letter::C, 1, 3, 0)).unwrap(),
                 "\"C♯3\"".to_string()
             );
         }
 
         #[test]
         fn test_deserialize() {
-            // Note::new("C", 1, 3, 0)
+            // Note::new(Letter::C, 1, 3, 0)
             let note: Note = serde_json::from_str("\"C♯3\"").unwrap();
             assert_eq!(note.pitch(), 49);
         }
+
+        #[test]
+        fn test_round_trip() {
+            for name in &["C", "C0", "C-1", "C+10", "B♭𝄳-3-5.5", "G9"] {
+                let note: Note = name.parse().unwrap();
+                let json = serde_json::to_string(&note).unwrap();
+                assert_eq!(serde_json::from_str::<Note>(&json).unwrap(), note);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Note;
+    use super::{Key, Letter, Note, NoteError, NoteParseError};
 
     #[test]
     fn test_display() {
-        let note = Note::new("C", 0, 0, 0);
-        assert_eq!(note.to_string(), "C".to_string());
+        let note = Note::new(Letter::C, 0, -1, 0);
+        assert_eq!(note.to_string(), "C-1".to_string());
         assert_eq!(note.pitch(), 0);
-        let note = Note::new("C", 0, 1, 0);
+        let note = Note::new(Letter::C, 0, 0, 0);
+        assert_eq!(note.to_string(), "C0".to_string());
+        assert_eq!(note.pitch(), 12);
+        let note = note.without_octave();
         assert_eq!(note.to_string(), "C".to_string());
         assert_eq!(note.pitch(), 12);
-        assert_eq!(Note::new("C", 1, 3, 0).to_string(), "C♯3".to_string());
+        assert_eq!(Note::new(Letter::C, 1, 3, 0).to_string(), "C♯3".to_string());
+    }
+
+    #[test]
+    fn test_try_new() {
+        let note = Note::try_new("e", -1, 5, 0).unwrap();
+        assert_eq!(note, Note::new(Letter::E, -1, 5, 0));
+        assert_eq!(note.letter(), Letter::E);
+        assert_eq!(
+            Note::try_new("H", 0, 5, 0),
+            Err(NoteError::InvalidPitchName("H".to_string()))
+        );
+        assert_eq!(
+            Note::try_new("Cb", 0, 5, 0),
+            Err(NoteError::InvalidPitchName("Cb".to_string()))
+        );
+        assert_eq!(
+            Note::try_new("C", 5, 5, 0),
+            Err(NoteError::AccidentalOutOfRange(5))
+        );
+        assert_eq!(
+            Note::try_new("C", 100, 5, 0),
+            Err(NoteError::AccidentalOutOfRange(100))
+        );
+        const MIDDLE_C: Note = Note::new(Letter::C, 0, 4, 0);
+        assert_eq!(MIDDLE_C.pitch(), 60);
+    }
+
+    #[test]
+    #[should_panic(expected = "accidental beyond a quadruple sharp or flat")]
+    fn test_new_out_of_range() {
+        Note::new(Letter::C, 5, 4, 0);
+    }
+
+    #[test]
+    fn test_midi() {
+        let parse = |name: &str| name.parse::<Note>().unwrap();
+        assert_eq!(parse("C4").midi_i32(), 60);
+        assert_eq!(parse("C-1").midi_i32(), 0);
+        assert_eq!(parse("Cb-1").midi_i32(), -1);
+        assert_eq!(parse("Cb-1").pitch(), 0);
+        assert_eq!(parse("A-5").midi_i32(), -39);
+        assert_eq!(parse("C4+50").midi_i32(), 60);
+        assert_eq!(parse("C♯4+50").midi_i32(), 62);
+        assert_eq!(parse("C4-50").midi_i32(), 60);
+        assert_eq!(parse("C4-51").midi_i32(), 59);
+        assert_eq!(parse("Cb-1-50").midi_i32(), -2);
+        assert_eq!(parse("C20").midi_i32(), 252);
+        assert_eq!(parse("E20").midi_i32(), 256);
+        assert_eq!(parse("E20").pitch(), 255);
+        assert_eq!(parse("C100").pitch_f32(), 1212.);
+
+        assert_eq!(parse("G9").try_midi_u7(), Ok(127));
+        assert_eq!(parse("C-1").try_midi_u7(), Ok(0));
+        assert_eq!(
+            parse("G#9").try_midi_u7(),
+            Err(NoteError::MidiOutOfRange(128))
+        );
+        assert_eq!(
+            parse("Cb-1").try_midi_u7(),
+            Err(NoteError::MidiOutOfRange(-1))
+        );
+
+        assert_eq!(Note::try_from_midi(-1.).unwrap().midi_i32(), -1);
+        assert_eq!(
+            Note::try_from_midi(1e6),
+            Err(NoteError::MidiOutOfRange(1_000_000))
+        );
+        assert!(Note::try_from_midi_in_key(1e6, &Key::default()).is_err());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let parse = |name: &str| name.parse::<Note>().unwrap();
+        let (b_sharp, c, d_flat_flat) = (parse("B#3"), parse("C4"), parse("Dbb4"));
+        assert_ne!(b_sharp, c);
+        assert!(b_sharp.is_enharmonic(&c));
+        assert_eq!(c.cmp_pitch(&d_flat_flat), std::cmp::Ordering::Equal);
+        assert!(b_sharp < c && c < d_flat_flat);
+        assert_eq!(
+            parse("B#3").cmp_spelling(&parse("Cb4")),
+            std::cmp::Ordering::Less
+        );
+        assert_eq!(parse("C4+10"), parse("C4+10.0"));
+        assert!(parse("C4") < parse("C4+1"));
+
+        let mut notes: Vec<_> = ["G4", "C#4", "Db4", "B3", "C4-50", "C4"]
+            .iter()
+            .map(|name| parse(name))
+            .collect();
+        notes.sort();
+        let names: Vec<_> = notes
+            .iter()
+            .map(|note| (note.letter(), note.accidental() as i8))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Letter::B, 0),
+                (Letter::C, 0),
+                (Letter::C, 0),
+                (Letter::C, 1),
+                (Letter::D, -1),
+                (Letter::G, 0)
+            ]
+        );
+
+        let mut counts = std::collections::HashMap::new();
+        for name in &["C4", "C4", "B#3"] {
+            *counts.entry(parse(name)).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&parse("C4")], 2);
+        assert_eq!(counts[&parse("B#3")], 1);
+    }
+
+    #[test]
+    fn test_octave() {
+        let parse = |name: &str| name.parse::<Note>().unwrap();
+        let (c, c0, c_1) = (parse("C"), parse("C0"), parse("C-1"));
+        assert_eq!(c.octave(), None);
+        assert!(c.is_pitch_class());
+        assert_eq!(c0.octave(), Some(0));
+        assert_eq!(c_1.octave(), Some(-1));
+        assert_ne!(c, c0);
+        assert!(c.is_enharmonic(&c0));
+        assert_eq!((c.pitch(), c0.pitch(), c_1.pitch()), (12, 12, 0));
+        for name in &[
+            "C",
+            "C0",
+            "C-1",
+            "C4",
+            "C0+10",
+            "D♯-2-5.5",
+            "E𝄳",
+            "G127",
+            "A-128",
+        ] {
+            let note = parse(name);
+            assert_eq!(&note.to_string(), name);
+            assert_eq!(parse(&note.to_string()), note);
+        }
+        assert!("C128".parse::<Note>().is_err());
+
+        let note = c.clone().with_octave(4);
+        assert_eq!(note, parse("C4"));
+        assert_eq!(note.without_octave(), c);
+        assert_eq!(parse("C+10").octave(), Some(10));
+        let note = c.clone().with_cents(10.);
+        assert_eq!(note.to_string(), "C0+10");
+        assert_eq!(note.pitch_f32(), 12.1);
+        assert_eq!(parse("C4+10").without_octave(), c);
+
+        let e_flat = c.transpose("m3".parse().unwrap()).unwrap();
+        assert_eq!(e_flat.to_string(), "E♭");
+        let d_sharp = parse("B").transpose("M3".parse().unwrap()).unwrap();
+        assert_eq!(d_sharp.to_string(), "D♯");
+        let names: Vec<_> = c.enharmonics().iter().map(|n| n.to_string()).collect();
+        assert_eq!(names, vec!["B♯", "C", "D𝄫"]);
+        let names: Vec<_> = parse("C4")
+            .enharmonics()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(names, vec!["B♯3", "C4", "D𝄫4"]);
+    }
+
+    #[test]
+    fn test_cents() {
+        let note = Note::new(Letter::A, 0, 4, 12);
+        assert_eq!(note.cents(), 12.);
+        assert_eq!(note.to_string(), "A4+12");
+        let note = Note::new(Letter::A, 0, 4, -30);
+        assert_eq!(note.to_string(), "A4-30");
+        assert!((note.pitch_f32() - 68.7).abs() < 1e-5);
+        assert_eq!(note.pitch(), 69);
+        let note = Note::new(Letter::A, 0, 4, 0).with_cents(12.5);
+        assert_eq!(note.cents(), 12.5);
+        assert_eq!(note.pitch_f32(), 69.125);
+        assert_eq!(note.to_string(), "A4+12.5");
+        let note = note.with_cents(-0.001);
+        assert_eq!(note.to_string(), "A4-0.001");
+        let note = note.with_cents(1234.25);
+        assert_eq!(note.to_string(), "A4+1234.25");
+        assert_eq!(note.pitch_f32(), 69. + 12.3425);
+        assert_eq!(Note::from_midi(61.305).cents(), 30.5);
+    }
+
+    #[test]
+    fn test_cents_round_trip() {
+        for cents in &[0., 1., -1., 12.5, -12.5, 99.999, -250.125, 1200.] {
+            let note = Note::new(Letter::C, 1, 5, 0).with_cents(*cents);
+            let parsed: Note = note.to_string().parse().unwrap();
+            assert_eq!(parsed.cents(), note.cents(), "{}", note);
+            assert_eq!(parsed, note);
+        }
+        let c4 = Note::new(Letter::C, 0, 4, 0);
+        for cents in &[-1e9, 1e9, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                c4.clone().try_with_cents(*cents),
+                Err(NoteError::CentsOutOfRange)
+            );
+        }
+        let note = c4.try_with_cents(-2_000_000.).unwrap();
+        assert_eq!(note.to_string().parse::<Note>().unwrap(), note);
+    }
+
+    #[test]
+    fn test_quarter_tones() {
+        let note = Note::from_quarter_tones(Letter::A, 1, 4, 0);
+        assert_eq!(note.to_string(), "A𝄲4");
+        assert_eq!(note.accidental(), 0.5);
+        assert_eq!(note.quarter_tones(), 1);
+        assert_eq!(note.pitch_f32(), 69.5);
+        let note = Note::from_quarter_tones(Letter::B, -3, 3, 0);
+        assert_eq!(note.to_string(), "B♭𝄳3");
+        assert_eq!(note.pitch_f32(), 57.5);
+        assert_eq!(Note::new(Letter::G, -1, 3, 0).quarter_tones(), -2);
+        assert_eq!(Note::new(Letter::G, -1, 3, 0).accidental(), -1.);
+        let note: Note = "E\u{E283}4".parse().unwrap();
+        assert_eq!(note.to_string(), "E♯𝄲4");
+    }
+
+    #[test]
+    fn test_hz() {
+        let note: Note = "A4".parse().unwrap();
+        assert_eq!(note.hz(440.), 440.);
+        assert_eq!(note.hz(432.), 432.);
+        let note: Note = "A3".parse().unwrap();
+        assert_eq!(note.hz(415.), 207.5);
+        let note: Note = "C4".parse().unwrap();
+        assert!((note.hz(440.) - 261.626).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_from_hz() {
+        let note = Note::from_hz(440., 440.);
+        assert_eq!(note.pitch(), 69);
+        assert_eq!(note.pitch_f32(), 69.);
+        let note = Note::from_hz(261.626, 440.);
+        assert_eq!(note.pitch(), 60);
+        let note = Note::from_hz(466.16, 440.);
+        assert_eq!(note.pitch(), 70);
+        let note = Note::from_hz(432., 440.);
+        assert_eq!(note.pitch(), 69);
+        assert!((note.pitch_f32() - 68.682).abs() < 1e-3);
+        let note = Note::from_hz(440., 415.);
+        assert!((note.pitch_f32() - 70.013).abs() < 1e-3);
+        let note = Note::from_midi(61.3);
+        assert!((note.pitch_f32() - 61.3).abs() < 1e-3);
+        assert_eq!(Note::from_midi(60.5).midi_i32(), 60);
+        assert_eq!(Note::from_midi(61.5).midi_i32(), 62);
+
+        assert_eq!(
+            Note::try_from_hz(0., 440.),
+            Err(NoteError::InvalidFrequency)
+        );
+        assert_eq!(
+            Note::try_from_hz(-1., 440.),
+            Err(NoteError::InvalidFrequency)
+        );
+        assert_eq!(
+            Note::try_from_hz(f32::NAN, 440.),
+            Err(NoteError::InvalidFrequency)
+        );
+        assert_eq!(Note::try_from_midi(f32::NAN), Err(NoteError::NonFiniteMidi));
+        assert_eq!(
+            Note::try_from_midi(f32::NEG_INFINITY),
+            Err(NoteError::NonFiniteMidi)
+        );
+        assert!(matches!(
+            Note::try_from_hz(1e-38, 440.),
+            Err(NoteError::MidiOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_midi_in_key() {
+        let key: Key = "Db:maj".parse().unwrap();
+        let note = Note::from_midi_in_key(61., &key);
+        assert_eq!((note.pitchname(), note.accidental()), ("D", -1.));
+        assert_eq!(note.pitch(), 61);
+        let key: Key = "C#:maj".parse().unwrap();
+        let note = Note::from_midi_in_key(60.25, &key);
+        assert_eq!((note.pitchname(), note.accidental()), ("B", 1.));
+        assert!((note.pitch_f32() - 60.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let notes = Note::parse_many("C, E, G");
+        let pitches: Vec<_> = notes.iter().map(|n| n.as_ref().unwrap().pitch()).collect();
+        assert_eq!(pitches, vec![12, 16, 19]);
+        let notes = Note::parse_many(" C4 E4\tG4 ");
+        let pitches: Vec<_> = notes.iter().map(|n| n.as_ref().unwrap().pitch()).collect();
+        assert_eq!(pitches, vec![60, 64, 67]);
+        assert!(Note::parse_many("  ").is_empty());
+
+        let input = "C4, X4 G4,, A4xyz";
+        let notes = Note::parse_many(input);
+        assert_eq!(notes.len(), 5);
+        assert!(notes[0].is_ok());
+        assert!(matches!(
+            &notes[1],
+            Err(NoteParseError::InvalidPitchName { span, .. }) if *span == (4..5)
+        ));
+        assert!(notes[2].is_ok());
+        assert!(matches!(
+            &notes[3],
+            Err(NoteParseError::Empty { span, .. }) if *span == (10..10)
+        ));
+        let err = notes[4].as_ref().unwrap_err();
+        assert_eq!(err.span(), 14..17);
+        assert_eq!(
+            err.diagnostic(input),
+            "C4, X4 G4,, A4xyz\n              ^^^ trailing input, expected end of input"
+        );
+    }
+
+    #[test]
+    fn test_enharmonics() {
+        let spell = |note: &Note| (note.pitchname(), note.accidental(), note.pitch());
+        let note: Note = "C4".parse().unwrap();
+        let notes: Vec<_> = note.enharmonics().iter().map(spell).collect();
+        assert_eq!(notes, vec![("B", 1., 60), ("C", 0., 60), ("D", -2., 60)]);
+        let note: Note = "G#4".parse().unwrap();
+        let notes: Vec<_> = note.enharmonics().iter().map(spell).collect();
+        assert_eq!(notes, vec![("G", 1., 68), ("A", -1., 68)]);
+        let note: Note = "C𝄲4".parse().unwrap();
+        let notes: Vec<_> = note.enharmonics().iter().map(spell).collect();
+        assert_eq!(notes, vec![("B", 1.5, 60), ("C", 0.5, 60), ("D", -1.5, 60)]);
+        let note: Note = "E4+15".parse().unwrap();
+        assert!(note
+            .enharmonics()
+            .iter()
+            .all(|note| (note.pitch_f32() - 64.15).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_respell() {
+        let spell = |note: Note| (note.pitchname(), note.accidental(), note.pitch());
+        for (input, sharp, flat, simplest) in &[
+            ("C4", ("C", 0., 60), ("C", 0., 60), ("C", 0., 60)),
+            ("B#3", ("C", 0., 60), ("C", 0., 60), ("C", 0., 60)),
+            ("Db4", ("C", 1., 61), ("D", -1., 61), ("D", -1., 61)),
+            ("C#4", ("C", 1., 61), ("D", -1., 61), ("C", 1., 61)),
+            ("Fbb4", ("D", 1., 63), ("E", -1., 63), ("E", -1., 63)),
+            ("E##4", ("F", 1., 66), ("G", -1., 66), ("F", 1., 66)),
+            ("Cb4", ("B", 0., 59), ("B", 0., 59), ("B", 0., 59)),
+        ] {
+            let note: Note = input.parse().unwrap();
+            assert_eq!(spell(note.respell_sharp()), *sharp, "{}", input);
+            assert_eq!(spell(note.respell_flat()), *flat, "{}", input);
+            assert_eq!(spell(note.simplest_spelling()), *simplest, "{}", input);
+        }
+        let note: Note = "B#3-30".parse().unwrap();
+        assert_eq!(note.simplest_spelling().cents(), -30.);
+    }
+
+    #[test]
+    fn test_svara() {
+        let sa: Note = "D4".parse().unwrap();
+        let note: Note = "F#4".parse().unwrap();
+        assert_eq!(note.svara_h(&sa, false, true, true), "Ga");
+        assert_eq!(note.svara_c(&sa, 29, true, true, true).unwrap(), "G₃");
+        let note: Note = "C#4".parse().unwrap();
+        assert_eq!(note.svara_h(&sa, true, true, false), "N,");
+        assert!(note.svara_c(&sa, 99, true, true, true).is_err());
+        assert_eq!(
+            note.svara_c(&sa, "dheerasankarabharanam", true, true, false)
+                .unwrap(),
+            "N3,"
+        );
     }
 }
@@ -0,0 +1,241 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::converters::note_converter::ParseOptions;
+use super::key::Key;
+use super::note::Note;
+use super::scale::Scale;
+use super::NoteParseError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NoteRangeError {
+    #[error("expected `start..end` or `start..=end`")]
+    MissingSeparator,
+    #[error("invalid start note: {0}")]
+    Start(NoteParseError),
+    #[error("invalid end note: {0}")]
+    End(NoteParseError),
+}
+
+/// A span of notes between two ends, such as `C4..=G5` or `C4..C5`.
+#[derive(Debug, Clone)]
+pub struct NoteRange {
+    start: Note,
+    end: Note,
+    inclusive: bool,
+}
+
+impl NoteRange {
+    pub fn new(start: Note, end: Note, inclusive: bool) -> Self {
+        NoteRange {
+            start,
+            end,
+            inclusive,
+        }
+    }
+
+    pub fn start(&self) -> &Note {
+        &self.start
+    }
+
+    pub fn end(&self) -> &Note {
+        &self.end
+    }
+
+    pub fn is_inclusive(&self) -> bool {
+        self.inclusive
+    }
+
+    /// MIDI numbers one semitone apart from the start, keeping its cents.
+    fn semitones(&self) -> impl Iterator<Item = f32> {
+        // tolerate the rounding of the cents
+        let (start, end) = (self.start.pitch_f32(), self.end.pitch_f32());
+        let last = if self.inclusive {
+            end + 1e-3
+        } else {
+            end - 1e-3
+        };
+        (0..)
+            .map(move |step| start + step as f32)
+            .take_while(move |&midi| midi <= last)
+    }
+
+    /// Every semitone of the range, spelled with sharps,
+    /// leaving out those beyond the octaves a note can hold.
+    pub fn chromatic(&self) -> impl Iterator<Item = Note> {
+        self.semitones()
+            .filter_map(|midi| Note::try_from_midi(midi).ok())
+    }
+
+    /// The notes of the range that belong to `key`, spelled as in `key`,
+    /// leaving out those beyond the octaves a note can hold.
+    pub fn in_key(&self, key: &Key) -> impl Iterator<Item = Note> {
+        let key = *key;
+        let degrees = key.degrees();
+        self.semitones()
+            .filter(move |midi| {
+                let pitch_class = (midi.round_ties_even() as i32).rem_euclid(12) as i8;
+                degrees.contains(&pitch_class)
+            })
+            .filter_map(move |midi| Note::try_from_midi_in_key(midi, &key).ok())
+    }
+
+    /// The notes of the range that belong to `scale`, spelled as in `scale`,
+    /// leaving out those beyond the octaves a note can hold.
+    pub fn in_scale(&self, scale: &Scale) -> impl Iterator<Item = Note> {
+        let notes = scale.notes();
+        self.semitones().filter_map(move |midi| {
+            let pitch = midi.round_ties_even();
+            let degree = notes
+                .iter()
+                .find(|note| (note.pitch_f32() - pitch).rem_euclid(12.) == 0.)?;
+            // saturates, so that anything too large is skipped below
+            let octaves = i8::try_from(((pitch - degree.pitch_f32()) / 12.) as i32).ok()?;
+            let octave = degree.octave().unwrap_or(0).checked_add(octaves)?;
+            let (letter, accidental) = degree.spelling();
+            Some(Note::new(letter, accidental, octave, 0).with_cents((midi - pitch) * 100.))
+        })
+    }
+}
+
+/// Error spans of the end note point into the whole input.
+impl FromStr for NoteRange {
+    type Err = NoteRangeError;
+    fn from_str(input: &str) -> Result<NoteRange, NoteRangeError> {
+        let split = input.find("..").ok_or(NoteRangeError::MissingSeparator)?;
+        let (start, end) = (&input[..split], &input[split + 2..]);
+        let (end, inclusive) = match end.strip_prefix('=') {
+            Some(end) => (end, true),
+            None => (end, false),
+        };
+        let offset = input.len() - end.len();
+
+        let options = ParseOptions::librosa();
+        let start = Note::parse_with(start, &options).map_err(NoteRangeError::Start)?;
+        let end = Note::parse_with(end, &options)
+            .map_err(|err| NoteRangeError::End(err.shifted(offset)))?;
+        Ok(NoteRange::new(start, end, inclusive))
+    }
+}
+
+impl std::fmt::Display for NoteRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{}{}", self.start, separator, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NoteRange, NoteRangeError};
+    use crate::core::key::Key;
+    use crate::core::scale::{Scale, ScaleKind};
+    use crate::core::NoteParseError;
+
+    #[test]
+    fn test_parse() {
+        let range: NoteRange = "C4..=G5".parse().unwrap();
+        assert_eq!(range.to_string(), "C4..=G5");
+        assert_eq!(range.start().pitch(), 60);
+        assert_eq!(range.end().pitch(), 79);
+        assert!(range.is_inclusive());
+        let range: NoteRange = "C4..C5".parse().unwrap();
+        assert!(!range.is_inclusive());
+
+        assert_eq!(
+            "C4-G5".parse::<NoteRange>().unwrap_err(),
+            NoteRangeError::MissingSeparator
+        );
+        assert!(matches!(
+            "X4..=G5".parse::<NoteRange>(),
+            Err(NoteRangeError::Start(
+                NoteParseError::InvalidPitchName { .. }
+            ))
+        ));
+        match "C4..=G5x".parse::<NoteRange>() {
+            Err(NoteRangeError::End(err)) => {
+                assert_eq!(err.span(), 7..8);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_chromatic() {
+        let range: NoteRange = "C4..=E4".parse().unwrap();
+        let notes: Vec<_> = range
+            .chromatic()
+            .map(|note| (note.pitchname(), note.accidental() as i8, note.pitch()))
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("C", 0, 60),
+                ("C", 1, 61),
+                ("D", 0, 62),
+                ("D", 1, 63),
+                ("E", 0, 64)
+            ]
+        );
+        let range: NoteRange = "C4..E4".parse().unwrap();
+        assert_eq!(range.chromatic().count(), 4);
+        let range: NoteRange = "E4..=C4".parse().unwrap();
+        assert_eq!(range.chromatic().count(), 0);
+        let range: NoteRange = "A4+25..=B4+25".parse().unwrap();
+        let pitches: Vec<_> = range.chromatic().map(|note| note.pitch_f32()).collect();
+        assert_eq!(pitches, vec![69.25, 70.25, 71.25]);
+    }
+
+    #[test]
+    fn test_in_key() {
+        let range: NoteRange = "C4..=C5".parse().unwrap();
+        let key: Key = "C:maj".parse().unwrap();
+        let pitches: Vec<_> = range.in_key(&key).map(|note| note.pitch()).collect();
+        assert_eq!(pitches, vec![60, 62, 64, 65, 67, 69, 71, 72]);
+
+        let range: NoteRange = "Db4..Db5".parse().unwrap();
+        let key: Key = "Db:maj".parse().unwrap();
+        let notes: Vec<_> = range
+            .in_key(&key)
+            .map(|note| (note.pitchname(), note.accidental() as i8))
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("D", -1),
+                ("E", -1),
+                ("F", 0),
+                ("G", -1),
+                ("A", -1),
+                ("B", -1),
+                ("C", 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_in_scale() {
+        let range: NoteRange = "C4..=C5".parse().unwrap();
+        let scale = Scale::new("Db2".parse().unwrap(), ScaleKind::MinorPentatonic);
+        let notes: Vec<_> = range
+            .in_scale(&scale)
+            .map(|note| (note.pitchname(), note.accidental() as i8, note.pitch()))
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("D", -1, 61),
+                ("F", -1, 64),
+                ("G", -1, 66),
+                ("A", -1, 68),
+                ("C", -1, 71)
+            ]
+        );
+        let range: NoteRange = "C127..=C#127+1300".parse().unwrap();
+        let scale = Scale::new("C4".parse().unwrap(), ScaleKind::Major);
+        let octaves: Vec<_> = range.in_scale(&scale).map(|note| note.octave()).collect();
+        assert_eq!(octaves, vec![Some(127); 7]);
+    }
+}
@@ -0,0 +1,408 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use super::convert::ParameterError;
+use super::note::Note;
+
+// Semitones above the tonic of the degrees of the major scale.
+const MAJOR_SEMITONES: [i8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// (degree, semitones above the tonic) of each note of a scale.
+type Pattern = &'static [(u8, i8)];
+
+const MAJOR: Pattern = &[(1, 0), (2, 2), (3, 4), (4, 5), (5, 7), (6, 9), (7, 11)];
+const NATURAL_MINOR: Pattern = &[(1, 0), (2, 2), (3, 3), (4, 5), (5, 7), (6, 8), (7, 10)];
+const HARMONIC_MINOR: Pattern = &[(1, 0), (2, 2), (3, 3), (4, 5), (5, 7), (6, 8), (7, 11)];
+const MELODIC_MINOR: Pattern = &[(1, 0), (2, 2), (3, 3), (4, 5), (5, 7), (6, 9), (7, 11)];
+const DORIAN: Pattern = &[(1, 0), (2, 2), (3, 3), (4, 5), (5, 7), (6, 9), (7, 10)];
+const PHRYGIAN: Pattern = &[(1, 0), (2, 1), (3, 3), (4, 5), (5, 7), (6, 8), (7, 10)];
+const LYDIAN: Pattern = &[(1, 0), (2, 2), (3, 4), (4, 6), (5, 7), (6, 9), (7, 11)];
+const MIXOLYDIAN: Pattern = &[(1, 0), (2, 2), (3, 4), (4, 5), (5, 7), (6, 9), (7, 10)];
+const LOCRIAN: Pattern = &[(1, 0), (2, 1), (3, 3), (4, 5), (5, 6), (6, 8), (7, 10)];
+const MAJOR_PENTATONIC: Pattern = &[(1, 0), (2, 2), (3, 4), (5, 7), (6, 9)];
+const MINOR_PENTATONIC: Pattern = &[(1, 0), (3, 3), (4, 5), (5, 7), (7, 10)];
+const BLUES: Pattern = &[(1, 0), (3, 3), (4, 5), (5, 6), (5, 7), (7, 10)];
+const WHOLE_TONE: Pattern = &[(1, 0), (2, 2), (3, 4), (4, 6), (5, 8), (6, 10)];
+const OCTATONIC_HALF_WHOLE: Pattern = &[
+    (1, 0),
+    (2, 1),
+    (3, 3),
+    (3, 4),
+    (4, 6),
+    (5, 7),
+    (6, 9),
+    (7, 10),
+];
+const OCTATONIC_WHOLE_HALF: Pattern = &[
+    (1, 0),
+    (2, 2),
+    (3, 3),
+    (4, 5),
+    (5, 6),
+    (6, 8),
+    (6, 9),
+    (7, 11),
+];
+
+/// The built-in scales and modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScaleKind {
+    Major,
+    NaturalMinor,
+    HarmonicMinor,
+    MelodicMinor,
+    Ionian,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Aeolian,
+    Locrian,
+    MajorPentatonic,
+    MinorPentatonic,
+    Blues,
+    WholeTone,
+    /// Alternating half and whole steps, such as C D♭ E♭ E F♯ G A B♭.
+    OctatonicHalfWhole,
+    /// Alternating whole and half steps, such as C D E♭ F G♭ A♭ A B.
+    OctatonicWholeHalf,
+}
+
+impl ScaleKind {
+    fn pattern(&self) -> Pattern {
+        match self {
+            ScaleKind::Major | ScaleKind::Ionian => MAJOR,
+            ScaleKind::NaturalMinor | ScaleKind::Aeolian => NATURAL_MINOR,
+            ScaleKind::HarmonicMinor => HARMONIC_MINOR,
+            ScaleKind::MelodicMinor => MELODIC_MINOR,
+            ScaleKind::Dorian => DORIAN,
+            ScaleKind::Phrygian => PHRYGIAN,
+            ScaleKind::Lydian => LYDIAN,
+            ScaleKind::Mixolydian => MIXOLYDIAN,
+            ScaleKind::Locrian => LOCRIAN,
+            ScaleKind::MajorPentatonic => MAJOR_PENTATONIC,
+            ScaleKind::MinorPentatonic => MINOR_PENTATONIC,
+            ScaleKind::Blues => BLUES,
+            ScaleKind::WholeTone => WHOLE_TONE,
+            ScaleKind::OctatonicHalfWhole => OCTATONIC_HALF_WHOLE,
+            ScaleKind::OctatonicWholeHalf => OCTATONIC_WHOLE_HALF,
+        }
+    }
+}
+
+/// Parses names such as `major`, `harmonic minor`, `dorian` or `whole-tone`, ignoring case.
+impl FromStr for ScaleKind {
+    type Err = ParameterError;
+    fn from_str(input: &str) -> Result<ScaleKind, ParameterError> {
+        let name: String = input
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .collect::<String>()
+            .to_lowercase();
+        let kind = match name.as_str() {
+            "major" | "maj" => ScaleKind::Major,
+            "minor" | "min" | "naturalminor" => ScaleKind::NaturalMinor,
+            "harmonicminor" => ScaleKind::HarmonicMinor,
+            "melodicminor" => ScaleKind::MelodicMinor,
+            "ionian" => ScaleKind::Ionian,
+            "dorian" => ScaleKind::Dorian,
+            "phrygian" => ScaleKind::Phrygian,
+            "lydian" => ScaleKind::Lydian,
+            "mixolydian" => ScaleKind::Mixolydian,
+            "aeolian" => ScaleKind::Aeolian,
+            "locrian" => ScaleKind::Locrian,
+            "pentatonic" | "majorpentatonic" => ScaleKind::MajorPentatonic,
+            "minorpentatonic" => ScaleKind::MinorPentatonic,
+            "blues" => ScaleKind::Blues,
+            "wholetone" => ScaleKind::WholeTone,
+            "octatonic" | "halfwhole" | "octatonichalfwhole" => ScaleKind::OctatonicHalfWhole,
+            "wholehalf" | "octatonicwholehalf" => ScaleKind::OctatonicWholeHalf,
+            _ => return Err(ParameterError::UnknownScale(input.to_string())),
+        };
+        Ok(kind)
+    }
+}
+
+/// A scale built on a tonic, its notes spelled after their degrees,
+/// so that D♭ major has a G♭ rather than an F♯.
+#[derive(Debug, Clone)]
+pub struct Scale {
+    tonic: Note,
+    kind: Option<ScaleKind>,
+    pattern: Vec<(u8, i8)>,
+}
+
+impl Scale {
+    pub fn new(tonic: Note, kind: ScaleKind) -> Self {
+        Scale {
+            tonic,
+            kind: Some(kind),
+            pattern: kind.pattern().to_vec(),
+        }
+    }
+
+    /// A scale of the given semitones above the tonic.
+    ///
+    /// Seven notes are spelled on consecutive letters, any other number of notes
+    /// after the nearest degree of the major scale, the flattened one on a tie,
+    /// so `[0, 3, 5, 6, 7, 10]` is spelled 1 ♭3 4 ♭5 5 ♭7.
+    ///
+    /// Fails unless the steps are strictly increasing within one octave, `0..12`.
+    pub fn custom(tonic: Note, semitones: &[i8]) -> Result<Self, ParameterError> {
+        let mut previous = -1;
+        for &step in semitones {
+            if step <= previous || step >= 12 {
+                return Err(ParameterError::InvalidStep(step));
+            }
+            previous = step;
+        }
+        let pattern = semitones
+            .iter()
+            .enumerate()
+            .map(|(i, &step)| {
+                let degree = if semitones.len() == 7 {
+                    i
+                } else {
+                    (0..7)
+                        .rev()
+                        .min_by_key(|&degree| (MAJOR_SEMITONES[degree] - step).abs())
+                        .unwrap()
+                };
+                (degree as u8 + 1, step)
+            })
+            .collect();
+        Ok(Scale {
+            tonic,
+            kind: None,
+            pattern,
+        })
+    }
+
+    pub fn tonic(&self) -> &Note {
+        &self.tonic
+    }
+
+    /// `None` for a [`Scale::custom`] scale.
+    pub fn kind(&self) -> Option<ScaleKind> {
+        self.kind
+    }
+
+    /// Number of notes per octave.
+    pub fn len(&self) -> usize {
+        self.pattern.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern.is_empty()
+    }
+
+    /// Semitones above the tonic of each note.
+    pub fn semitones(&self) -> Vec<i8> {
+        self.pattern
+            .iter()
+            .map(|&(_, semitones)| semitones)
+            .collect()
+    }
+
+    /// One octave of the scale, from the tonic up,
+    /// leaving out notes past octave 127 or beyond a quadruple sharp or flat.
+    pub fn notes(&self) -> Vec<Note> {
+        self.pattern
+            .iter()
+            .filter_map(|&(degree, semitones)| self.tonic.above(degree, semitones))
+            .collect()
+    }
+
+    /// The note of the (1-based) `degree`, continuing into the octaves above
+    /// for degrees past [`Scale::len`], `None` past octave 127.
+    pub fn note(&self, degree: usize) -> Option<Note> {
+        let index = degree.checked_sub(1).filter(|_| !self.is_empty())?;
+        let octaves = i8::try_from(index / self.len()).ok()?;
+        let (degree, semitones) = *self.pattern.get(index % self.len())?;
+        let semitones = octaves.checked_mul(12)?.checked_add(semitones)?;
+        let degree = (octaves as u8).checked_mul(7)?.checked_add(degree)?;
+        self.tonic.above(degree, semitones)
+    }
+
+    /// The (1-based) degree of `note` in this scale, in any octave.
+    ///
+    /// The spelling has to match, so F♯ is not found in D♭ major.
+    pub fn degree_of(&self, note: &Note) -> Option<usize> {
+        let spelling = note.spelling();
+        // by pattern index, since `notes` leaves out what it cannot represent
+        self.pattern
+            .iter()
+            .position(|&(degree, semitones)| {
+                self.tonic
+                    .above(degree, semitones)
+                    .is_some_and(|degree| degree.spelling() == spelling)
+            })
+            .map(|index| index + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scale, ScaleKind};
+    use crate::core::convert::ParameterError;
+    use crate::core::Note;
+
+    fn names(notes: &[Note]) -> Vec<(&'static str, i8)> {
+        notes
+            .iter()
+            .map(|note| (note.pitchname(), note.accidental() as i8))
+            .collect()
+    }
+
+    #[test]
+    fn test_spelling() {
+        let scale = Scale::new("Db4".parse().unwrap(), ScaleKind::Major);
+        assert_eq!(
+            names(&scale.notes()),
+            vec![
+                ("D", -1),
+                ("E", -1),
+                ("F", 0),
+                ("G", -1),
+                ("A", -1),
+                ("B", -1),
+                ("C", 0)
+            ]
+        );
+        let pitches: Vec<_> = scale.notes().iter().map(|note| note.pitch()).collect();
+        assert_eq!(pitches, vec![61, 63, 65, 66, 68, 70, 72]);
+
+        let scale = Scale::new("F#4".parse().unwrap(), ScaleKind::Major);
+        assert_eq!(names(&scale.notes())[6], ("E", 1));
+        let scale = Scale::new("C4".parse().unwrap(), ScaleKind::HarmonicMinor);
+        assert_eq!(
+            names(&scale.notes()),
+            vec![
+                ("C", 0),
+                ("D", 0),
+                ("E", -1),
+                ("F", 0),
+                ("G", 0),
+                ("A", -1),
+                ("B", 0)
+            ]
+        );
+        let scale = Scale::new("G#4".parse().unwrap(), ScaleKind::HarmonicMinor);
+        assert_eq!(names(&scale.notes())[6], ("F", 2));
+    }
+
+    #[test]
+    fn test_kinds() {
+        let c: Note = "C4".parse().unwrap();
+        for (kind, semitones) in &[
+            (ScaleKind::Major, vec![0, 2, 4, 5, 7, 9, 11]),
+            (ScaleKind::NaturalMinor, vec![0, 2, 3, 5, 7, 8, 10]),
+            (ScaleKind::MelodicMinor, vec![0, 2, 3, 5, 7, 9, 11]),
+            (ScaleKind::Dorian, vec![0, 2, 3, 5, 7, 9, 10]),
+            (ScaleKind::Phrygian, vec![0, 1, 3, 5, 7, 8, 10]),
+            (ScaleKind::Lydian, vec![0, 2, 4, 6, 7, 9, 11]),
+            (ScaleKind::Mixolydian, vec![0, 2, 4, 5, 7, 9, 10]),
+            (ScaleKind::Locrian, vec![0, 1, 3, 5, 6, 8, 10]),
+            (ScaleKind::MajorPentatonic, vec![0, 2, 4, 7, 9]),
+            (ScaleKind::MinorPentatonic, vec![0, 3, 5, 7, 10]),
+            (ScaleKind::Blues, vec![0, 3, 5, 6, 7, 10]),
+            (ScaleKind::WholeTone, vec![0, 2, 4, 6, 8, 10]),
+            (ScaleKind::OctatonicHalfWhole, vec![0, 1, 3, 4, 6, 7, 9, 10]),
+            (ScaleKind::OctatonicWholeHalf, vec![0, 2, 3, 5, 6, 8, 9, 11]),
+        ] {
+            let scale = Scale::new(c.clone(), *kind);
+            assert_eq!(&scale.semitones(), semitones, "{:?}", kind);
+            let pitches: Vec<_> = scale
+                .notes()
+                .iter()
+                .map(|note| note.pitch() as i8 - 60)
+                .collect();
+            assert_eq!(&pitches, semitones, "{:?}", kind);
+        }
+        let scale = Scale::new(c, ScaleKind::Blues);
+        assert_eq!(
+            names(&scale.notes()),
+            vec![
+                ("C", 0),
+                ("E", -1),
+                ("F", 0),
+                ("G", -1),
+                ("G", 0),
+                ("B", -1)
+            ]
+        );
+        assert_eq!(
+            "Harmonic Minor".parse::<ScaleKind>().unwrap(),
+            ScaleKind::HarmonicMinor
+        );
+        assert_eq!(
+            "whole-tone".parse::<ScaleKind>().unwrap(),
+            ScaleKind::WholeTone
+        );
+        assert!("bebop".parse::<ScaleKind>().is_err());
+    }
+
+    #[test]
+    fn test_custom() {
+        let scale = Scale::custom("C4".parse().unwrap(), &[0, 3, 5, 6, 7, 10]).unwrap();
+        assert_eq!(scale.kind(), None);
+        assert_eq!(
+            names(&scale.notes()),
+            names(&Scale::new("C4".parse().unwrap(), ScaleKind::Blues).notes())
+        );
+        // Hungarian minor, spelled on consecutive letters
+        let scale = Scale::custom("A4".parse().unwrap(), &[0, 2, 3, 6, 7, 8, 11]).unwrap();
+        assert_eq!(
+            names(&scale.notes()),
+            vec![
+                ("A", 0),
+                ("B", 0),
+                ("C", 0),
+                ("D", 1),
+                ("E", 0),
+                ("F", 0),
+                ("G", 1)
+            ]
+        );
+        for (steps, step) in &[
+            (&[-1, 0, 4][..], -1),
+            (&[0, 4, 12], 12),
+            (&[0, 4, 4, 7], 4),
+            (&[0, 2, 4, 5, 9, 7, 11], 7),
+        ] {
+            assert!(matches!(
+                Scale::custom("C4".parse().unwrap(), steps),
+                Err(ParameterError::InvalidStep(s)) if s == *step
+            ));
+        }
+    }
+
+    #[test]
+    fn test_degrees() {
+        let scale = Scale::new("Db4".parse().unwrap(), ScaleKind::Major);
+        assert_eq!(scale.degree_of(&"Gb2".parse().unwrap()), Some(4));
+        assert_eq!(scale.degree_of(&"F#4".parse().unwrap()), None);
+        assert_eq!(scale.degree_of(&"Db4".parse().unwrap()), Some(1));
+        assert_eq!(scale.degree_of(&"D4".parse().unwrap()), None);
+        // the seventh, B with five flats, has no spelling
+        let chromatic = Scale::custom("C4".parse().unwrap(), &[0, 1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(chromatic.notes().len(), 6);
+        assert_eq!(chromatic.degree_of(&"A𝄫𝄫4".parse().unwrap()), Some(6));
+        assert_eq!(scale.note(1).unwrap().pitch(), 61);
+        assert_eq!(scale.note(4).unwrap().pitch(), 66);
+        assert_eq!(scale.note(8).unwrap().pitch(), 73);
+        assert_eq!(scale.note(11).unwrap().pitch(), 78);
+        assert_eq!(names(&[scale.note(11).unwrap()]), vec![("G", -1)]);
+        assert!(scale.note(0).is_none());
+        assert!(Scale::custom("C4".parse().unwrap(), &[])
+            .unwrap()
+            .note(1)
+            .is_none());
+        let scale = Scale::new("C4".parse().unwrap(), ScaleKind::Major);
+        assert_eq!(scale.note(71).unwrap().pitch(), 180);
+        assert!(scale.note(78).is_none());
+        assert!(scale.note(80).is_none());
+        let scale = Scale::new("C4".parse().unwrap(), ScaleKind::MajorPentatonic);
+        assert_eq!(scale.note(6).unwrap().pitch(), 72);
+    }
+}
@@ -1 +1,7 @@
+#[cfg(feature = "parser-nom")]
+pub mod by_nom;
+#[cfg(feature = "parser-pest")]
+pub mod by_pest;
+#[cfg(feature = "parser-regex")]
+pub mod by_regex;
 pub mod core;